@@ -0,0 +1,35 @@
+#!/usr/bin/env python3
+"""示例机器人：朝果子走，避开墙、障碍物和蛇身，无路可走时直走。
+
+每行从标准输入读到一个局面（JSON），向标准输出写一个方向。
+"""
+import json
+import sys
+
+MOVES = {"Up": (0, -1), "Down": (0, 1), "Left": (-1, 0), "Right": (1, 0)}
+OPPOSITE = {"Up": "Down", "Down": "Up", "Left": "Right", "Right": "Left"}
+
+for line in sys.stdin:
+    obs = json.loads(line)
+    w, h = obs["width"], obs["height"]
+    blocked = {tuple(p) for p in obs["obstacles"]}
+    blocked |= {tuple(p) for p in obs["snake"][:-1]}
+    for body in obs["ai_snakes"]:
+        blocked |= {tuple(p) for p in body}
+    hx, hy = obs["snake"][0]
+    target = obs["food"] or (obs["leftover_food"] or [None])[0]
+
+    def safe(d):
+        dx, dy = MOVES[d]
+        x, y = hx + dx, hy + dy
+        return 0 < x < w - 1 and 0 < y < h - 1 and (x, y) not in blocked
+
+    def distance(d):
+        if target is None:
+            return 0
+        dx, dy = MOVES[d]
+        return abs(hx + dx - target[0]) + abs(hy + dy - target[1])
+
+    choices = [d for d in MOVES if d != OPPOSITE[obs["direction"]] and safe(d)]
+    choice = min(choices, key=distance) if choices else obs["direction"]
+    print(json.dumps({"tick": obs["tick"], "dir": choice}), flush=True)
@@ -0,0 +1,69 @@
+use snake::snake_game::bot::{BotDriver, BotProcess, DEFAULT_DEADLINE};
+use snake::snake_game::game::Game;
+use snake::snake_game::level::Campaign;
+use snake::snake_game::sim::{play, Pilot, RunEnd};
+use rand::Rng;
+use std::path::Path;
+use std::time::Duration;
+
+/// 默认战役清单
+const CAMPAIGN_PATH: &str = "assets/levels/campaign.toml";
+
+/// 默认最多模拟的步数（60 步一秒，约一小时）
+const DEFAULT_MAX_TICKS: u64 = 60 * 60 * 60;
+
+/// 读取命令行参数 `name` 后面紧跟的值
+fn arg_value(name: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    let pos = args.iter().position(|a| a == name)?;
+    args.get(pos + 1).cloned()
+}
+
+/// 无窗口地让外部机器人玩一局，不按真实时间，机器人回答得快就跑得快
+///
+/// 用法：`snake-bot --bot "<命令>" [--seed <种子>] [--campaign <清单>] [--deadline <毫秒>] [--max-ticks <步数>] [--save-replay <文件>]`
+fn main() {
+    let command = arg_value("--bot").expect("用 --bot 指定机器人命令");
+    let campaign = match arg_value("--campaign") {
+        Some(path) => Campaign::load(Path::new(&path)).expect("无法读取战役文件"),
+        None if Path::new(CAMPAIGN_PATH).exists() => Campaign::load(Path::new(CAMPAIGN_PATH)).unwrap_or_else(|e| {
+            eprintln!("读取默认战役失败，改用无尽模式: {}", e);
+            Campaign::endless(30, 30)
+        }),
+        None => Campaign::endless(30, 30),
+    };
+    let seed = arg_value("--seed")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(|| rand::thread_rng().gen());
+    let deadline = arg_value("--deadline")
+        .and_then(|s| s.parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_DEADLINE);
+    let max_ticks = arg_value("--max-ticks")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_MAX_TICKS);
+
+    let bot = BotProcess::spawn(&command).expect("无法启动机器人");
+    let mut pilot = Pilot::Bot(BotDriver::new(bot, deadline));
+    let mut game = Game::with_campaign(campaign, seed);
+    let result = play(&mut game, &mut pilot, max_ticks);
+
+    if let Some(path) = arg_value("--save-replay") {
+        if let Err(e) = game.get_replay().save(Path::new(&path)) {
+            eprintln!("回放保存失败: {}", e);
+        }
+    }
+    let end = match result.end {
+        RunEnd::Died => "死亡",
+        RunEnd::BoardFull => "梦境已满",
+        RunEnd::MaxTicks => "达到步数上限",
+    };
+    let (timeouts, moves) = match &pilot {
+        Pilot::Bot(driver) => (driver.timeouts(), driver.moves()),
+        Pilot::Strategy(_) => (0, 0),
+    };
+    println!(
+        "种子 {}  结果 {}  分数 {}  关卡 {}  模拟步 {}  超时 {}/{}",
+        seed, end, result.score, result.level, result.ticks, timeouts, moves
+    );
+}
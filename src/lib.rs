@@ -0,0 +1,9 @@
+//! 梦魇贪吃蛇
+//!
+//! `snake_game` 与 `snake_snake` 是不依赖任何窗口的模拟核心，
+//! `snake_window` 是基于 piston_window 的渲染前端（`piston` feature）。
+
+pub mod snake_game;
+pub mod snake_snake;
+#[cfg(feature = "piston")]
+pub mod snake_window;
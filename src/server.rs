@@ -0,0 +1,272 @@
+use snake::snake_game::game::{Game, Input, MAX_CATCH_UP_TICKS, MAX_PLAYERS, TICK};
+use snake::snake_game::level::Campaign;
+use snake::snake_game::net::{
+    read_message, write_message, ClientMessage, ServerMessage, Snapshot, CONNECTION_TIMEOUT, DEFAULT_ADDR,
+    HANDSHAKE_TIMEOUT, PROTOCOL_VERSION,
+};
+use snake::snake_snake::snake::Direction;
+use rand::Rng;
+use std::collections::HashMap;
+use std::io::{BufReader, ErrorKind};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 默认战役清单
+const CAMPAIGN_PATH: &str = "assets/levels/campaign.toml";
+
+/// 每个客户端最多积压的待发消息，发不出去的局面直接丢弃，慢的客户端只会跳帧
+const OUTBOX_SIZE: usize = 32;
+
+/// 名字的最大长度
+const MAX_NAME_LEN: usize = 12;
+
+/// 连接线程发给模拟线程的事件
+enum ClientEvent {
+    /// 握手成功
+    Joined {
+        id: u64,
+        name: String,
+        stream: TcpStream,
+        outbox: SyncSender<Arc<String>>,
+    },
+    /// 改变方向
+    Turn { id: u64, dir: Direction },
+    /// 断开（主动离开、超时或出错）
+    Left { id: u64 },
+}
+
+/// 一个已加入的客户端
+struct Connection {
+    player: usize,
+    stream: TcpStream,
+    outbox: SyncSender<Arc<String>>,
+    /// 下一帧是否需要带上障碍物
+    needs_obstacles: bool,
+}
+
+/// 读取命令行参数 `name` 后面紧跟的值
+fn arg_value(name: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    let pos = args.iter().position(|a| a == name)?;
+    args.get(pos + 1).cloned()
+}
+
+/// 权威服务器：模拟只在这里进行，客户端只发送方向、接收每个模拟步的局面
+///
+/// 用法：`snake-server [--addr 127.0.0.1:7878] [--campaign <清单>] [--seed <种子>]`
+fn main() {
+    let addr = arg_value("--addr").unwrap_or_else(|| DEFAULT_ADDR.to_string());
+    let campaign = match arg_value("--campaign") {
+        Some(path) => Campaign::load(Path::new(&path)).expect("无法读取战役文件"),
+        None if Path::new(CAMPAIGN_PATH).exists() => Campaign::load(Path::new(CAMPAIGN_PATH)).unwrap_or_else(|e| {
+            eprintln!("读取默认战役失败，改用无尽模式: {}", e);
+            Campaign::endless(30, 30)
+        }),
+        None => Campaign::endless(30, 30),
+    };
+    let seed = arg_value("--seed")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(|| rand::thread_rng().gen());
+    let listener = TcpListener::bind(&addr).expect("无法监听地址");
+    println!("服务器已启动：{}（协议版本 {}，种子 {}）", addr, PROTOCOL_VERSION, seed);
+
+    let (events_tx, events) = mpsc::channel();
+    thread::spawn(move || accept_loop(listener, events_tx));
+    run(Game::new_online(campaign, seed), events);
+}
+
+/// 接受新连接，每个连接一个线程
+fn accept_loop(listener: TcpListener, events: Sender<ClientEvent>) {
+    let mut next_id = 0;
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let events = events.clone();
+                let id = next_id;
+                next_id += 1;
+                thread::spawn(move || handle_client(id, stream, events));
+            }
+            Err(e) => eprintln!("接受连接失败: {}", e),
+        }
+    }
+}
+
+/// 一个连接的读线程：先握手，之后把方向转交给模拟线程；超时、出错或对方离开时通知模拟线程
+fn handle_client(id: u64, stream: TcpStream, events: Sender<ClientEvent>) {
+    let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+    let setup = stream
+        .set_nodelay(true)
+        .and_then(|_| stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)))
+        .and_then(|_| stream.set_write_timeout(Some(CONNECTION_TIMEOUT)))
+        .and_then(|_| stream.try_clone());
+    let (mut writer, mut reader) = match setup.and_then(|w| Ok((w, BufReader::new(stream.try_clone()?)))) {
+        Ok(pair) => pair,
+        Err(e) => {
+            eprintln!("{} 连接设置失败: {}", peer, e);
+            return;
+        }
+    };
+    // 握手：第一条消息必须是版本一致的 Hello
+    let name = match read_message(&mut reader) {
+        Ok(Some(ClientMessage::Hello { version, name })) if version == PROTOCOL_VERSION => {
+            name.chars().take(MAX_NAME_LEN).collect::<String>()
+        }
+        Ok(Some(ClientMessage::Hello { version, .. })) => {
+            let reason = format!("协议版本不一致：服务器 {}，客户端 {}", PROTOCOL_VERSION, version);
+            let _ = write_message(&mut writer, &ServerMessage::Rejected { reason });
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+        _ => {
+            eprintln!("{} 握手失败", peer);
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+    };
+    // 写线程：模拟线程只往队列里放，不会被慢的客户端卡住
+    let (outbox, pending): (SyncSender<Arc<String>>, Receiver<Arc<String>>) = mpsc::sync_channel(OUTBOX_SIZE);
+    thread::spawn(move || {
+        use std::io::Write;
+        for line in pending {
+            if writer.write_all(line.as_bytes()).is_err() {
+                let _ = writer.shutdown(Shutdown::Both);
+                break;
+            }
+        }
+    });
+    let joined = ClientEvent::Joined {
+        id,
+        name,
+        stream: match stream.try_clone() {
+            Ok(s) => s,
+            Err(_) => return,
+        },
+        outbox,
+    };
+    if events.send(joined).is_err() || stream.set_read_timeout(Some(CONNECTION_TIMEOUT)).is_err() {
+        return;
+    }
+    loop {
+        match read_message(&mut reader) {
+            Ok(Some(ClientMessage::Turn { dir })) => {
+                if events.send(ClientEvent::Turn { id, dir }).is_err() {
+                    return;
+                }
+            }
+            Ok(Some(ClientMessage::Ping)) | Ok(Some(ClientMessage::Hello { .. })) => {}
+            Ok(Some(ClientMessage::Bye)) | Ok(None) => break,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                eprintln!("{} 超过 {} 秒没有消息，断开", peer, CONNECTION_TIMEOUT.as_secs());
+                break;
+            }
+            Err(e) => {
+                eprintln!("{} 断开: {}", peer, e);
+                break;
+            }
+        }
+    }
+    let _ = events.send(ClientEvent::Left { id });
+}
+
+/// 模拟线程：按固定步长推进游戏，每一步把局面发给所有客户端
+fn run(mut game: Game, events: Receiver<ClientEvent>) {
+    let mut connections: HashMap<u64, Connection> = HashMap::new();
+    let mut names = vec![String::new(); MAX_PLAYERS];
+    let mut obstacles_version = game.get_obstacles_version();
+    let mut next_tick = Instant::now();
+    loop {
+        let mut inputs = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            match event {
+                ClientEvent::Joined { id, name, stream, outbox } => {
+                    // 中途加入的玩家在安全的空位出生，第一帧带上障碍物
+                    let (width, height) = game.get_size();
+                    match game.join_player() {
+                        Some(player) => {
+                            println!("{} 加入，玩家位置 {}", name, player);
+                            names[player] = name;
+                            let welcome = ServerMessage::Welcome { player, width, height };
+                            let _ = outbox.try_send(Arc::new(encode(&welcome)));
+                            connections.insert(id, Connection { player, stream, outbox, needs_obstacles: true });
+                        }
+                        None => {
+                            let rejected = ServerMessage::Rejected { reason: "服务器已满".to_string() };
+                            let _ = outbox.try_send(Arc::new(encode(&rejected)));
+                            let _ = stream.shutdown(Shutdown::Read);
+                        }
+                    }
+                }
+                ClientEvent::Turn { id, dir } => {
+                    if let Some(conn) = connections.get(&id) {
+                        inputs.push(Input::TurnPlayer { player: conn.player as u8, dir });
+                    }
+                }
+                ClientEvent::Left { id } => {
+                    if let Some(conn) = connections.remove(&id) {
+                        println!("{} 离开", names[conn.player]);
+                        game.leave_player(conn.player);
+                        names[conn.player].clear();
+                        let _ = conn.stream.shutdown(Shutdown::Both);
+                    }
+                }
+            }
+        }
+
+        // 落后太多时丢掉多余的模拟步，与单机的 `Game::step` 一致
+        let now = Instant::now();
+        let mut steps = 0;
+        while next_tick <= now {
+            if steps == MAX_CATCH_UP_TICKS {
+                next_tick = now;
+                break;
+            }
+            let inputs = std::mem::take(&mut inputs);
+            game.tick(&inputs);
+            next_tick += Duration::from_secs_f64(TICK);
+            steps += 1;
+        }
+        if steps > 0 {
+            broadcast(&game, &names, &mut connections, &mut obstacles_version);
+        }
+        thread::sleep(next_tick.saturating_duration_since(Instant::now()));
+    }
+}
+
+/// 把当前局面发给所有客户端，障碍物只在变化时或新玩家的第一帧发送
+fn broadcast(game: &Game, names: &[String], connections: &mut HashMap<u64, Connection>, obstacles_version: &mut u64) {
+    let changed = game.get_obstacles_version() != *obstacles_version;
+    *obstacles_version = game.get_obstacles_version();
+    let mut full = None;
+    let mut delta = None;
+    let mut gone = Vec::new();
+    for (&id, conn) in connections.iter_mut() {
+        let line = if changed || conn.needs_obstacles {
+            full.get_or_insert_with(|| Arc::new(encode(&ServerMessage::Snapshot(Snapshot::capture(game, names, true)))))
+        } else {
+            delta.get_or_insert_with(|| Arc::new(encode(&ServerMessage::Snapshot(Snapshot::capture(game, names, false)))))
+        };
+        match conn.outbox.try_send(line.clone()) {
+            Ok(()) => conn.needs_obstacles = false,
+            // 积压满了就跳过这一帧，跳过的障碍物变化留到下一次发成功时补上
+            Err(TrySendError::Full(_)) => conn.needs_obstacles |= changed,
+            Err(TrySendError::Disconnected(_)) => gone.push(id),
+        }
+    }
+    // 写线程已经退出，读线程随后也会报告离开
+    for id in gone {
+        if let Some(conn) = connections.get(&id) {
+            let _ = conn.stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// 编码成一行 JSON
+fn encode(msg: &ServerMessage) -> String {
+    let mut buf = Vec::new();
+    write_message(&mut buf, msg).expect("写入内存不会失败");
+    String::from_utf8(buf).expect("JSON 是合法的 UTF-8")
+}
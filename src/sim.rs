@@ -0,0 +1,218 @@
+use snake::snake_game::bot::{BotDriver, BotProcess, DEFAULT_DEADLINE};
+use snake::snake_game::game::{DeathCause, Game};
+use snake::snake_game::level::Campaign;
+use snake::snake_game::sim::{play, Pilot, RunEnd, RunResult};
+use snake::snake_snake::ai::AiStrategy;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 默认战役清单
+const CAMPAIGN_PATH: &str = "assets/levels/campaign.toml";
+
+/// 默认每局最多模拟的步数（60 步一秒，10 分钟）
+const DEFAULT_MAX_TICKS: u64 = 60 * 60 * 10;
+
+/// 读取命令行参数 `name` 后面紧跟的值
+fn arg_value(name: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    let pos = args.iter().position(|a| a == name)?;
+    args.get(pos + 1).cloned()
+}
+
+/// 用法说明，参数有误时打印
+const USAGE: &str = "用法：snake-sim [--games 100] [--seed 0] [--strategy food_seeker | --bot \"<命令>\"] \
+[--campaign <清单> | --size 30x30] [--max-ticks <步数>] [--deadline <毫秒>] [--format csv|json] [--out <文件>]";
+
+/// 结果的输出格式
+enum Format {
+    Csv,
+    Json,
+}
+
+/// 解析好的命令行参数
+struct Options {
+    games: u64,
+    first_seed: u64,
+    max_ticks: u64,
+    campaign: Campaign,
+    strategy: AiStrategy,
+    bot: Option<String>,
+    deadline: Duration,
+    format: Format,
+    out: Option<String>,
+}
+
+/// 读取数值参数 `name`，没有指定时为 None
+fn number_arg<T: FromStr>(name: &str) -> Result<Option<T>, String> {
+    arg_value(name)
+        .map(|s| s.parse().map_err(|_| format!("{} 的值 {} 不是合法的数字", name, s)))
+        .transpose()
+}
+
+/// 解析并检查命令行参数
+fn parse_args() -> Result<Options, String> {
+    // 指定 --size 时使用该大小的无尽模式，战役的游戏区大小由关卡决定，不能再指定 --size
+    let campaign = match (arg_value("--campaign"), arg_value("--size")) {
+        (Some(_), Some(_)) => {
+            return Err("--campaign 和 --size 不能同时使用，战役的游戏区大小由关卡文件决定".to_string());
+        }
+        (Some(path), None) => {
+            Campaign::load(Path::new(&path)).map_err(|e| format!("无法读取战役文件 {}: {}", path, e))?
+        }
+        (None, Some(size)) => {
+            let (width, height) = size
+                .split_once('x')
+                .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                .ok_or_else(|| format!("--size 的格式是 宽x高，例如 30x30，而不是 {}", size))?;
+            let campaign = Campaign::endless(width, height);
+            campaign.validate().map_err(|e| format!("--size {}：{}", size, e))?;
+            campaign
+        }
+        (None, None) if Path::new(CAMPAIGN_PATH).exists() => {
+            Campaign::load(Path::new(CAMPAIGN_PATH)).map_err(|e| format!("无法读取默认战役: {}", e))?
+        }
+        (None, None) => Campaign::endless(30, 30),
+    };
+    let strategy = match arg_value("--strategy").as_deref() {
+        None | Some("food_seeker") => AiStrategy::FoodSeeker,
+        Some("wander") => AiStrategy::Wander,
+        Some("hunter") => AiStrategy::Hunter,
+        Some("survivor") => AiStrategy::Survivor,
+        Some(other) => return Err(format!("未知的策略 {}，可选 wander、food_seeker、hunter、survivor", other)),
+    };
+    let format = match arg_value("--format").as_deref() {
+        None | Some("csv") => Format::Csv,
+        Some("json") => Format::Json,
+        Some(other) => return Err(format!("未知的输出格式 {}，可选 csv、json", other)),
+    };
+    Ok(Options {
+        games: number_arg("--games")?.unwrap_or(100),
+        first_seed: number_arg("--seed")?.unwrap_or(0),
+        max_ticks: number_arg("--max-ticks")?.unwrap_or(DEFAULT_MAX_TICKS),
+        campaign,
+        strategy,
+        bot: arg_value("--bot"),
+        deadline: number_arg("--deadline")?.map(Duration::from_millis).unwrap_or(DEFAULT_DEADLINE),
+        format,
+        out: arg_value("--out"),
+    })
+}
+
+/// 无窗口地批量模拟，每局输出一行统计，用数据来调关卡目标、障碍物密度和AI速度
+///
+/// 用法见 `USAGE`，第 i 局的种子是 `--seed` + i，同样的参数总是得到同样的结果。
+/// 参数有误时退出码为 2，运行中出错（机器人启动失败、写不出结果）时为 1。
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(e) = run(&options) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+/// 按参数模拟全部对局并写出结果
+fn run(options: &Options) -> Result<(), String> {
+    let mut results = Vec::new();
+    for seed in options.first_seed..options.first_seed + options.games {
+        // 外部机器人每局重新启动，不让上一局的状态影响下一局
+        let mut pilot = match &options.bot {
+            Some(command) => Pilot::Bot(BotDriver::new(
+                BotProcess::spawn(command).map_err(|e| format!("无法启动机器人 {}: {}", command, e))?,
+                options.deadline,
+            )),
+            None => Pilot::Strategy(options.strategy),
+        };
+        let mut game = Game::with_campaign(options.campaign.clone(), seed);
+        results.push(play(&mut game, &mut pilot, options.max_ticks));
+    }
+
+    let out: Box<dyn Write> = match &options.out {
+        Some(path) => Box::new(File::create(path).map_err(|e| format!("无法创建输出文件 {}: {}", path, e))?),
+        None => Box::new(io::stdout()),
+    };
+    let mut out = BufWriter::new(out);
+    match options.format {
+        Format::Csv => write_csv(&mut out, &results),
+        Format::Json => serde_json::to_writer_pretty(&mut out, &results)
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(out)),
+    }
+    .and_then(|_| out.flush())
+    .map_err(|e| format!("写出结果失败: {}", e))?;
+    print_summary(&results);
+    Ok(())
+}
+
+/// 每局一行：种子、结束方式、死因、分数、关卡、存活模拟步、蛇长、吃到的果子
+fn write_csv<W: Write>(w: &mut W, results: &[RunResult]) -> io::Result<()> {
+    writeln!(w, "seed,end,cause,score,level,ticks,length,food")?;
+    for r in results {
+        writeln!(
+            w,
+            "{},{},{},{},{},{},{},{}",
+            r.seed,
+            end_name(r.end),
+            r.cause.map(cause_name).unwrap_or(""),
+            r.score,
+            r.level,
+            r.ticks,
+            r.length,
+            r.food
+        )?;
+    }
+    Ok(())
+}
+
+/// 在标准错误上打印平均值和死因分布，不影响重定向的结果
+fn print_summary(results: &[RunResult]) {
+    if results.is_empty() {
+        return;
+    }
+    let n = results.len() as f64;
+    let mean = |f: fn(&RunResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+    let mut ends: BTreeMap<&str, usize> = BTreeMap::new();
+    for r in results {
+        let key = r.cause.map(cause_name).unwrap_or_else(|| end_name(r.end));
+        *ends.entry(key).or_default() += 1;
+    }
+    eprintln!(
+        "{} 局  平均分数 {:.2}  平均关卡 {:.2}  平均存活 {:.1} 秒",
+        results.len(),
+        mean(|r| r.score as f64),
+        mean(|r| r.level as f64),
+        mean(|r| r.ticks as f64) / 60.0
+    );
+    let ends: Vec<String> = ends.iter().map(|(k, v)| format!("{} {}", k, v)).collect();
+    eprintln!("结束方式：{}", ends.join("  "));
+}
+
+fn end_name(end: RunEnd) -> &'static str {
+    match end {
+        RunEnd::Died => "died",
+        RunEnd::BoardFull => "board_full",
+        RunEnd::MaxTicks => "max_ticks",
+    }
+}
+
+fn cause_name(cause: DeathCause) -> &'static str {
+    match cause {
+        DeathCause::Wall => "wall",
+        DeathCause::SelfBite => "self_bite",
+        DeathCause::Obstacle => "obstacle",
+        DeathCause::EggObstacle => "egg_obstacle",
+        DeathCause::AiSnake { .. } => "ai_snake",
+        DeathCause::Player => "player",
+    }
+}
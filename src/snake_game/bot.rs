@@ -0,0 +1,217 @@
+use crate::snake_game::game::{Game, GameEvent, Input, MAX_CATCH_UP_TICKS, TICK};
+use crate::snake_snake::snake::Direction;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 机器人每一步默认的思考时限
+pub const DEFAULT_DEADLINE: Duration = Duration::from_millis(50);
+
+/// 第一次询问额外多等的时间，留给机器人启动（例如解释器加载）
+const STARTUP_GRACE: Duration = Duration::from_secs(1);
+
+/// 发给机器人的局面，每当玩家蛇要移动时写一行 JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Observation {
+    /// 当前模拟步，机器人回答时可以带上它
+    pub tick: u64,
+    /// 游戏区大小（含边框），坐标 0 和 width-1 / height-1 是墙
+    pub width: i32,
+    pub height: i32,
+    /// 玩家蛇，蛇头在前
+    pub snake: Vec<(i32, i32)>,
+    /// 玩家蛇当前朝向
+    pub direction: Direction,
+    /// 每条AI蛇的身体，蛇头在前
+    pub ai_snakes: Vec<Vec<(i32, i32)>>,
+    /// 障碍物
+    pub obstacles: Vec<(i32, i32)>,
+    /// 果子坐标
+    pub food: Option<(i32, i32)>,
+    /// AI蛇留下的残骸果子
+    pub leftover_food: Vec<(i32, i32)>,
+    pub score: u32,
+    pub level: u32,
+}
+
+impl Observation {
+    /// 记录当前局面
+    pub fn capture(game: &Game) -> Observation {
+        let (width, height) = game.get_size();
+        let snake = game.get_snake();
+        Observation {
+            tick: game.get_tick(),
+            width,
+            height,
+            snake: snake.body().map(|b| (b.x, b.y)).collect(),
+            direction: snake.head_direction(),
+            ai_snakes: game
+                .ai_snakes
+                .iter()
+                .map(|ai| ai.body.iter().map(|b| (b.x, b.y)).collect())
+                .collect(),
+            obstacles: game.get_obstacles().clone(),
+            food: game.get_food(),
+            leftover_food: game.get_leftover_food().clone(),
+            score: game.get_score(),
+            level: game.get_level(),
+        }
+    }
+}
+
+/// 机器人的回答：直接写方向 `"Up"`，或者带上模拟步 `{"tick": 12, "dir": "Up"}`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Reply {
+    Plain(Direction),
+    Tagged { tick: u64, dir: Direction },
+}
+
+/// 外部机器人进程：局面写到它的标准输入，方向从它的标准输出读回
+pub struct BotProcess {
+    child: Child,
+    stdin: ChildStdin,
+    replies: Receiver<String>,
+}
+
+impl BotProcess {
+    /// 启动机器人，`command` 按空白分成程序和参数，例如 `python3 bots/greedy.py`
+    pub fn spawn(command: &str) -> io::Result<BotProcess> {
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "机器人命令为空"))?;
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("标准输入已设为管道");
+        let stdout = child.stdout.take().expect("标准输出已设为管道");
+        // 读线程：机器人写慢了也不会卡住游戏
+        let (tx, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(BotProcess { child, stdin, replies })
+    }
+
+    /// 把局面发给机器人，在 `deadline` 内等它回答方向
+    ///
+    /// 超时、回答无法解析、回答的是别的模拟步或机器人已经退出时返回 None。
+    pub fn ask(&mut self, obs: &Observation, deadline: Duration) -> Option<Direction> {
+        // 上一步超时后才到的回答已经作废
+        while self.replies.try_recv().is_ok() {}
+        let mut line = serde_json::to_vec(obs).ok()?;
+        line.push(b'\n');
+        self.stdin.write_all(&line).and_then(|_| self.stdin.flush()).ok()?;
+        let until = Instant::now() + deadline;
+        loop {
+            let line = match self.replies.recv_timeout(until.saturating_duration_since(Instant::now())) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => return None,
+            };
+            match serde_json::from_str(line.trim()) {
+                Ok(Reply::Plain(dir)) => return Some(dir),
+                Ok(Reply::Tagged { tick, dir }) if tick == obs.tick => return Some(dir),
+                // 旧的模拟步的回答，继续等
+                Ok(Reply::Tagged { .. }) => {}
+                Err(_) => {
+                    eprintln!("机器人的回答无法解析: {}", line);
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for BotProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// 机器人驾驶员：用机器人的回答代替键盘，仍然由 `Game` 按原来的规则推进
+///
+/// 只在玩家蛇要移动的那一步询问机器人；超时的机器人不转向，蛇继续直走。
+pub struct BotDriver {
+    bot: BotProcess,
+    deadline: Duration,
+    accumulator: f64,
+    /// 询问机器人的次数
+    moves: u64,
+    /// 超时或回答无效的次数
+    timeouts: u64,
+}
+
+impl BotDriver {
+    pub fn new(bot: BotProcess, deadline: Duration) -> BotDriver {
+        BotDriver {
+            bot,
+            deadline,
+            accumulator: 0.0,
+            moves: 0,
+            timeouts: 0,
+        }
+    }
+
+    /// 按真实时间推进，与 `Game::step` 一样最多补跑 `MAX_CATCH_UP_TICKS` 步；`inputs` 是其它输入（暂停、重开等）
+    pub fn advance(&mut self, game: &mut Game, delta_time: f64, inputs: &[Input]) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        let mut inputs = inputs;
+        self.accumulator += delta_time;
+        let mut steps = 0;
+        while self.accumulator >= TICK {
+            if steps == MAX_CATCH_UP_TICKS {
+                self.accumulator = 0.0;
+                break;
+            }
+            events.extend(self.step_tick(game, inputs));
+            inputs = &[];
+            self.accumulator -= TICK;
+            steps += 1;
+        }
+        // 这一帧没有执行模拟步时也要处理输入，否则暂停键会丢
+        for &input in inputs {
+            game.handle_input(input);
+        }
+        events
+    }
+
+    /// 应用 `inputs`，需要时询问机器人，然后执行一个模拟步
+    pub fn step_tick(&mut self, game: &mut Game, inputs: &[Input]) -> Vec<GameEvent> {
+        for &input in inputs {
+            game.handle_input(input);
+        }
+        let mut turn = Vec::new();
+        if game.is_player_move_due() {
+            self.moves += 1;
+            let deadline = if self.moves == 1 { self.deadline + STARTUP_GRACE } else { self.deadline };
+            match self.bot.ask(&Observation::capture(game), deadline) {
+                Some(dir) if dir != game.get_snake().head_direction() => turn.push(Input::Turn(dir)),
+                Some(_) => {}
+                None => self.timeouts += 1,
+            }
+        }
+        game.tick(&turn)
+    }
+
+    /// 询问机器人的次数
+    pub fn moves(&self) -> u64 {
+        self.moves
+    }
+
+    /// 机器人没有按时给出有效回答的次数
+    pub fn timeouts(&self) -> u64 {
+        self.timeouts
+    }
+}
@@ -0,0 +1,290 @@
+use crate::snake_game::game::{Game, GameEvent, Input};
+use crate::snake_game::grid::Occupant;
+use crate::snake_game::level::Campaign;
+use crate::snake_snake::snake::Direction;
+use serde::{Deserialize, Serialize};
+
+/// 网格观测的通道数：边框、障碍物、自己的身体、自己的蛇头、AI蛇、果子
+pub const GRID_CHANNELS: usize = 6;
+
+/// 射线观测的方向数，从正前方开始顺时针每 45 度一条
+pub const RAY_COUNT: usize = 8;
+
+/// 每条射线的特征：到墙或障碍物、到自己的身体、到AI蛇、到果子的距离倒数（看不到为 0）
+pub const RAY_FEATURES: usize = 4;
+
+/// 可选的动作，下标即动作编号
+pub const ACTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+/// 观测的编码方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObservationKind {
+    /// 多通道网格，按 `[通道][y][x]` 展开，每格 0 或 1
+    #[default]
+    Grid,
+    /// 以蛇头朝向为基准的射线特征，外加果子相对蛇头的前方、右方偏移（按游戏区大小归一化）
+    Rays,
+}
+
+/// 奖励塑形，每一步的奖励是发生的事件对应的值之和
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rewards {
+    /// 吃到一个果子（包括AI蛇的残骸）
+    pub eat: f32,
+    /// 死亡
+    pub death: f32,
+    /// 每走一步，通常为负数，促使蛇尽快找到果子
+    pub step: f32,
+    /// 完成一关
+    pub level_clear: f32,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Rewards {
+            eat: 1.0,
+            death: -1.0,
+            step: -0.01,
+            level_clear: 5.0,
+        }
+    }
+}
+
+/// 环境配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvConfig {
+    pub campaign: Campaign,
+    #[serde(default)]
+    pub observation: ObservationKind,
+    #[serde(default)]
+    pub rewards: Rewards,
+    /// 每局最多的步数，达到后 `done` 且 `truncated`，None 为不限
+    #[serde(default)]
+    pub max_steps: Option<u64>,
+}
+
+impl EnvConfig {
+    /// 使用默认的观测和奖励
+    pub fn new(campaign: Campaign) -> EnvConfig {
+        EnvConfig {
+            campaign,
+            observation: ObservationKind::default(),
+            rewards: Rewards::default(),
+            max_steps: None,
+        }
+    }
+}
+
+/// 一步的附加信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepInfo {
+    pub score: u32,
+    pub level: u32,
+    /// 蛇的长度
+    pub length: usize,
+    /// 本局已经走的步数
+    pub steps: u64,
+    /// 本局已经执行的模拟步
+    pub ticks: u64,
+    /// 因为达到 `max_steps` 而结束，而不是死亡
+    pub truncated: bool,
+    /// 这一步里发生的游戏事件
+    pub events: Vec<GameEvent>,
+}
+
+/// 强化学习环境：每一步是玩家蛇的一次移动，中间的模拟步（AI蛇、计时等）都由 `Game` 按原来的规则执行
+///
+/// 没有窗口，也不按真实时间，过关后自动进入下一关。
+pub struct SnakeEnv {
+    config: EnvConfig,
+    game: Game,
+    steps: u64,
+}
+
+impl SnakeEnv {
+    /// 创建环境，随后需要先调用 `reset`
+    pub fn new(config: EnvConfig) -> SnakeEnv {
+        let game = Game::with_campaign(config.campaign.clone(), 0);
+        SnakeEnv { config, game, steps: 0 }
+    }
+
+    /// 用 `seed` 开始新的一局，返回初始观测
+    pub fn reset(&mut self, seed: u64) -> Vec<f32> {
+        self.game = Game::with_campaign(self.config.campaign.clone(), seed);
+        self.steps = 0;
+        self.observe()
+    }
+
+    /// 朝 `action` 移动一步，返回 (观测, 奖励, 是否结束, 附加信息)
+    ///
+    /// 与当前方向相反的动作被忽略，蛇继续直走；结束后再调用只会返回同样的终局。
+    pub fn step(&mut self, action: Direction) -> (Vec<f32>, f32, bool, StepInfo) {
+        let rewards = self.config.rewards;
+        let mut events = Vec::new();
+        if !self.game.is_game_over() {
+            self.steps += 1;
+            // 执行模拟步直到玩家蛇移动一格
+            loop {
+                if self.game.waiting_next_level {
+                    self.game.handle_input(Input::NextLevel);
+                }
+                let due = self.game.is_player_move_due();
+                let inputs = if due && action != self.game.get_snake().head_direction() {
+                    vec![Input::Turn(action)]
+                } else {
+                    Vec::new()
+                };
+                events.extend(self.game.tick(&inputs));
+                if due || self.game.is_game_over() {
+                    break;
+                }
+            }
+        }
+
+        let mut reward = rewards.step;
+        for event in &events {
+            reward += match event {
+                GameEvent::FoodEaten { .. } => rewards.eat,
+                GameEvent::LevelCleared { .. } => rewards.level_clear,
+                GameEvent::Died { .. } => rewards.death,
+                _ => 0.0,
+            };
+        }
+        let truncated = !self.game.is_game_over() && self.config.max_steps.is_some_and(|max| self.steps >= max);
+        let done = self.game.is_game_over() || truncated;
+        let info = StepInfo {
+            score: self.game.get_score(),
+            level: self.game.get_level(),
+            length: self.game.get_snake().body().count(),
+            steps: self.steps,
+            ticks: self.game.get_tick(),
+            truncated,
+            events,
+        };
+        (self.observe(), reward, done, info)
+    }
+
+    /// 按动作编号（`ACTIONS` 的下标）移动一步
+    pub fn step_index(&mut self, action: usize) -> (Vec<f32>, f32, bool, StepInfo) {
+        self.step(ACTIONS[action % ACTIONS.len()])
+    }
+
+    /// 观测的形状：网格为 `[通道, 高, 宽]`，射线为 `[特征数]`
+    pub fn observation_shape(&self) -> Vec<usize> {
+        match self.config.observation {
+            ObservationKind::Grid => {
+                let (width, height) = self.game.get_size();
+                vec![GRID_CHANNELS, height as usize, width as usize]
+            }
+            ObservationKind::Rays => vec![RAY_COUNT * RAY_FEATURES + 2],
+        }
+    }
+
+    /// 当前局面的观测
+    pub fn observe(&self) -> Vec<f32> {
+        match self.config.observation {
+            ObservationKind::Grid => self.observe_grid(),
+            ObservationKind::Rays => self.observe_rays(),
+        }
+    }
+
+    /// 当前这一局
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    fn observe_grid(&self) -> Vec<f32> {
+        let (width, height) = self.game.get_size();
+        let plane = (width * height) as usize;
+        let mut obs = vec![0.0; GRID_CHANNELS * plane];
+        let grid = self.game.get_grid();
+        for y in 0..height {
+            for x in 0..width {
+                let i = (y * width + x) as usize;
+                if grid.is_wall(x, y) {
+                    obs[i] = 1.0;
+                }
+                if grid.has_obstacle(x, y) {
+                    obs[plane + i] = 1.0;
+                }
+                if grid.has_player(x, y) {
+                    obs[2 * plane + i] = 1.0;
+                }
+                if grid.has_ai(x, y) {
+                    obs[4 * plane + i] = 1.0;
+                }
+                if grid.has_food(x, y) {
+                    obs[5 * plane + i] = 1.0;
+                }
+            }
+        }
+        let (hx, hy) = self.game.get_snake_head();
+        if !grid.is_wall(hx, hy) {
+            obs[3 * plane + (hy * width + hx) as usize] = 1.0;
+        }
+        obs
+    }
+
+    fn observe_rays(&self) -> Vec<f32> {
+        let grid = self.game.get_grid();
+        let (width, height) = self.game.get_size();
+        let (hx, hy) = self.game.get_snake_head();
+        let forward = unit(self.game.get_snake().head_direction());
+        // 屏幕坐标 y 向下，向右转即 (x, y) -> (-y, x)
+        let right = (-forward.1, forward.0);
+        let rays = [
+            forward,
+            (forward.0 + right.0, forward.1 + right.1),
+            right,
+            (right.0 - forward.0, right.1 - forward.1),
+            (-forward.0, -forward.1),
+            (-forward.0 - right.0, -forward.1 - right.1),
+            (-right.0, -right.1),
+            (forward.0 - right.0, forward.1 - right.1),
+        ];
+        let mut obs = Vec::with_capacity(RAY_COUNT * RAY_FEATURES + 2);
+        for (dx, dy) in rays {
+            let mut features = [0.0; RAY_FEATURES];
+            let (mut x, mut y) = (hx, hy);
+            for distance in 1.. {
+                x += dx;
+                y += dy;
+                let slot = match grid.occupant(x, y) {
+                    Occupant::Wall | Occupant::Obstacle => 0,
+                    Occupant::Player => 1,
+                    Occupant::AiSnake => 2,
+                    Occupant::Food => 3,
+                    Occupant::Empty => continue,
+                };
+                if features[slot] == 0.0 {
+                    features[slot] = 1.0 / distance as f32;
+                }
+                if slot == 0 {
+                    break;
+                }
+            }
+            obs.extend_from_slice(&features);
+        }
+        // 果子相对蛇头的偏移，投影到前方和右方
+        let (fx, fy) = match self.game.get_food() {
+            Some((x, y)) => ((x - hx) as f32, (y - hy) as f32),
+            None => (0.0, 0.0),
+        };
+        let scale = width.max(height) as f32;
+        obs.push((fx * forward.0 as f32 + fy * forward.1 as f32) / scale);
+        obs.push((fx * right.0 as f32 + fy * right.1 as f32) / scale);
+        obs
+    }
+}
+
+/// 方向对应的单位向量
+fn unit(dir: Direction) -> (i32, i32) {
+    match dir {
+        Direction::Up => (0, -1),
+        Direction::Down => (0, 1),
+        Direction::Left => (-1, 0),
+        Direction::Right => (1, 0),
+    }
+}
@@ -0,0 +1,125 @@
+use crate::snake_game::game::{DeathCause, Game, GameEvent};
+use std::collections::BTreeMap;
+
+/// 游戏事件的订阅者：渲染、音效、成就、统计等各自实现，不需要比较前后的状态来猜发生了什么
+pub trait EventListener {
+    /// 每个事件调用一次，`game` 是这一帧模拟结束后的局面
+    fn on_event(&mut self, game: &Game, event: &GameEvent);
+}
+
+impl<F: FnMut(&Game, &GameEvent)> EventListener for F {
+    fn on_event(&mut self, game: &Game, event: &GameEvent) {
+        self(game, event)
+    }
+}
+
+/// 把 `Game::step` 返回的事件按订阅顺序分发给所有订阅者
+#[derive(Default)]
+pub struct EventBus {
+    listeners: Vec<Box<dyn EventListener>>,
+}
+
+impl EventBus {
+    pub fn new() -> EventBus {
+        EventBus::default()
+    }
+
+    /// 订阅之后发布的全部事件
+    pub fn subscribe<L: EventListener + 'static>(&mut self, listener: L) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// 把一批事件依次发给每个订阅者
+    pub fn publish(&mut self, game: &Game, events: &[GameEvent]) {
+        for event in events {
+            for listener in &mut self.listeners {
+                listener.on_event(game, event);
+            }
+        }
+    }
+}
+
+/// 按事件累计的统计，可以给成就或数据分析用
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventStats {
+    /// 玩家吃到的果子（包括AI蛇的残骸）
+    pub food_eaten: u32,
+    /// 完成的关卡
+    pub levels_cleared: u32,
+    /// AI蛇产下的卵
+    pub eggs_laid: u32,
+    /// 出现过的AI蛇
+    pub ai_spawned: u32,
+    /// 死亡的AI蛇
+    pub ai_died: u32,
+    /// 按死因统计的玩家死亡
+    pub deaths: BTreeMap<DeathCause, u32>,
+    /// 暂停次数
+    pub pauses: u32,
+}
+
+impl EventStats {
+    /// 累计一个事件
+    pub fn record(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::FoodEaten { .. } => self.food_eaten += 1,
+            GameEvent::LevelCleared { .. } => self.levels_cleared += 1,
+            GameEvent::EggLaid { .. } => self.eggs_laid += 1,
+            GameEvent::AiSpawned { .. } => self.ai_spawned += 1,
+            GameEvent::AiSnakeDied { .. } => self.ai_died += 1,
+            GameEvent::Died { cause, .. } => *self.deaths.entry(*cause).or_default() += 1,
+            GameEvent::Paused { paused: true } => self.pauses += 1,
+            _ => {}
+        }
+    }
+}
+
+impl EventListener for EventStats {
+    fn on_event(&mut self, _game: &Game, event: &GameEvent) {
+        self.record(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn bus_delivers_every_event_to_every_listener_in_order() {
+        let game = Game::new_with_seed(20, 20, 0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut bus = EventBus::new();
+        for id in 0..2 {
+            let seen = Rc::clone(&seen);
+            bus.subscribe(move |_: &Game, event: &GameEvent| seen.borrow_mut().push((id, event.clone())));
+        }
+        let events = [GameEvent::FoodEaten { x: 1, y: 2 }, GameEvent::BoardFull];
+        bus.publish(&game, &events);
+        assert_eq!(
+            *seen.borrow(),
+            [(0, events[0].clone()), (1, events[0].clone()), (0, events[1].clone()), (1, events[1].clone())]
+        );
+    }
+
+    #[test]
+    fn stats_count_deaths_by_cause_and_only_pauses_not_resumes() {
+        let mut stats = EventStats::default();
+        let died = |cause| GameEvent::Died { player: 0, cause, pos: (1, 1) };
+        for event in [
+            died(DeathCause::Wall),
+            died(DeathCause::Wall),
+            died(DeathCause::SelfBite),
+            GameEvent::Paused { paused: true },
+            GameEvent::Paused { paused: false },
+            GameEvent::EggLaid { x: 3, y: 3 },
+        ] {
+            stats.record(&event);
+        }
+        assert_eq!(stats.deaths[&DeathCause::Wall], 2);
+        assert_eq!(stats.deaths[&DeathCause::SelfBite], 1);
+        assert_eq!(stats.pauses, 1);
+        assert_eq!(stats.eggs_laid, 1);
+    }
+}
@@ -1,27 +1,205 @@
-use crate::snake_snake::snake::{Direction, Snake, AISnake};
-use crate::snake_window::draw::{draw_block, draw_rectangle};
-use piston_window::rectangle::Shape;
-use piston_window::types::Color;
-use piston_window::{Context, G2d, Key};
-use piston_window::Glyphs;
-use rand::{thread_rng, Rng};
-
-/// 食物颜色
-const FOOD_COLOR: Color = [0.8, 0.2, 1.0, 1.0]; // 亮紫色
-/// 上边框颜色
-const T_BORDER_COLOR: Color = [0.0000, 0.5, 0.5, 0.6];
-/// 下边框颜色
-const B_BORDER_COLOR: Color = [0.0000, 0.5, 0.5, 0.6];
-/// 左边框颜色
-const L_BORDER_COLOR: Color = [0.0000, 0.5, 0.5, 0.6];
-/// 右边框颜色
-const R_BORDER_COLOR: Color = [0.0000, 0.5, 0.5, 0.6];
-
-///游戏结束颜色
-const GAMEOVER_COLOR: Color = [0.90, 0.00, 0.00, 0.5];
-
-/// 移动周期，每过多长时间进行一次移动
-const MOVING_PERIOD: f64 = 0.18;
+use crate::snake_game::grid::Grid;
+use crate::snake_game::level::{AiRemains, AiSpawn, Campaign, LevelDef, ObstacleLayout, SpawnTrigger};
+use crate::snake_game::replay::Replay;
+use crate::snake_game::speed::SpeedCurve;
+use crate::snake_snake::ai::{AiStrategy, AiView};
+use crate::snake_snake::snake::{AISnake, Color, Direction, Snake, AI_HEAD_COLOR};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+/// 固定模拟步长（秒），玩家和AI蛇都按这个节拍推进
+pub const TICK: f64 = 1.0 / 60.0;
+
+/// 一次 `step` 最多追赶的模拟步数，超出部分直接丢弃，避免卡顿后连锁卡死
+pub const MAX_CATCH_UP_TICKS: u32 = 8;
+
+/// 生成随机障碍物时蛇头正前方保持畅通的格数
+const SPAWN_CLEARANCE: i32 = 3;
+
+/// 玩家杀死一条AI蛇得到的分数（不计入过关目标）
+pub const AI_KILL_SCORE: u32 = 2;
+
+/// AI蛇出现前的预警时间（秒），预警期间不会伤人
+pub const AI_TELEGRAPH_TIME: f64 = 1.5;
+
+/// AI蛇出生时蛇头与玩家蛇头的最小距离（曼哈顿距离）
+const AI_SPAWN_MIN_DISTANCE: i32 = 8;
+
+/// 联机时同一个游戏区最多的玩家数
+pub const MAX_PLAYERS: usize = 8;
+
+/// 每位玩家最多排队的转向，蛇每移动一格取出一个
+pub const MAX_QUEUED_TURNS: usize = 3;
+
+/// 游戏输入，与具体的窗口/按键无关
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Input {
+    /// 改变蛇的方向
+    Turn(Direction),
+    /// 双人对战时改变第二位玩家的方向
+    Turn2(Direction),
+    /// 联机时改变第 `player` 位玩家的方向（从 0 开始）
+    TurnPlayer { player: u8, dir: Direction },
+    /// 暂停/启动游戏
+    TogglePause,
+    /// 重新开始游戏
+    Restart,
+    /// 从头重玩当前关卡
+    RestartLevel,
+    /// 过关后进入下一关
+    NextLevel,
+}
+
+/// 一次 `step` 中发生的游戏事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    /// 玩家吃到了果子
+    FoodEaten { x: i32, y: i32 },
+    /// AI蛇在该位置产下了卵（变为障碍物）
+    EggLaid { x: i32, y: i32 },
+    /// 当前关卡目标达成
+    LevelCleared { level: u32 },
+    /// AI蛇吃到了果子
+    AiSnakeAte { x: i32, y: i32 },
+    /// AI蛇死亡，坐标为死亡时的蛇头
+    AiSnakeDied { x: i32, y: i32 },
+    /// 一条AI蛇出现，坐标为蛇头
+    AiSpawned { x: i32, y: i32 },
+    /// 第 `player` 位玩家死亡（单人游戏只有第 0 位），`pos` 为死亡时的蛇头
+    ///
+    /// 单人游戏和最后存活的对战随后还有一个 `GameOver`；联机和先吃到N个的对战随后在出生点复活。
+    Died { player: usize, cause: DeathCause, pos: (i32, i32) },
+    /// 游戏结束，坐标为死亡时的蛇头
+    GameOver { x: i32, y: i32 },
+    /// 暂停或继续
+    Paused { paused: bool },
+    /// 已经没有能放果子的空地，玩家获胜（随后还会有一个 `GameOver`）
+    BoardFull,
+    /// 双人对战分出胜负（随后还会有一个 `GameOver`，坐标为输家的蛇头）
+    VersusOver { winner: Winner },
+    /// 重新开始了这一局（`level_only` 为只重玩当前关卡）
+    Restarted { level_only: bool },
+}
+
+/// 双人对战的胜负规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VersusMode {
+    /// 最后存活的一方获胜
+    LastStanding,
+    /// 先吃到 `food` 个果子的一方获胜，死亡后在出生点复活，分数保留
+    FirstTo { food: u32 },
+}
+
+/// 玩家蛇的死因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeathCause {
+    /// 撞上边框
+    Wall,
+    /// 咬到自己
+    SelfBite,
+    /// 撞上障碍物
+    Obstacle,
+    /// 撞上AI蛇产下的卵
+    EggObstacle,
+    /// 撞上编号为 `id` 的AI蛇，或与更长的它蛇头相撞
+    AiSnake { id: u32 },
+    /// 撞上另一位玩家
+    Player,
+}
+
+impl DeathCause {
+    /// 显示在死亡画面上的说明
+    pub fn description(&self) -> String {
+        match self {
+            DeathCause::Wall => "撞上了梦境的边缘".to_string(),
+            DeathCause::SelfBite => "咬到了自己".to_string(),
+            DeathCause::Obstacle => "撞上了障碍物".to_string(),
+            DeathCause::EggObstacle => "撞上了AI蛇产下的卵".to_string(),
+            DeathCause::AiSnake { id } => format!("被第 {} 条AI蛇吞噬", id),
+            DeathCause::Player => "撞上了另一位玩家".to_string(),
+        }
+    }
+}
+
+/// 双人对战的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Winner {
+    PlayerOne,
+    PlayerTwo,
+    /// 同归于尽
+    Draw,
+}
+
+/// 双人对战的状态，第二位玩家是 `Game::get_players` 中的第一个
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Versus {
+    /// 胜负规则
+    mode: VersusMode,
+    /// 分出胜负后的结果
+    winner: Option<Winner>,
+}
+
+impl Versus {
+    /// 胜负规则
+    pub fn mode(&self) -> VersusMode {
+        self.mode
+    }
+
+    /// 对战结果，还没分出胜负时为 None
+    pub fn winner(&self) -> Option<Winner> {
+        self.winner
+    }
+}
+
+/// 第一位玩家以外的玩家：本地对战的第二位玩家，或联机时加入的其他玩家
+///
+/// 第一位玩家沿用 `Game` 自己的蛇和分数。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Player {
+    /// 蛇的主体
+    snake: Snake,
+    /// 当前分数
+    score: u32,
+    /// 等待时间
+    waiting_time: f64,
+    /// 这个位置是否有人，联机时玩家离开后蛇从游戏区移除，位置留给后来的玩家
+    active: bool,
+    /// 排队等待的转向
+    #[serde(default)]
+    turn_queue: VecDeque<Direction>,
+}
+
+impl Player {
+    fn new(snake: Snake) -> Player {
+        Player {
+            snake,
+            score: 0,
+            waiting_time: 0.0,
+            active: true,
+            turn_queue: VecDeque::new(),
+        }
+    }
+
+    /// 玩家的蛇
+    pub fn snake(&self) -> &Snake {
+        &self.snake
+    }
+
+    /// 玩家的分数
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// 这个位置是否有人
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
 
 /// AI蛇油滴粒子
 #[derive(Debug, Clone)]
@@ -34,8 +212,22 @@ pub struct AIOilParticle {
     pub max_life: f64, // 初始寿命
 }
 
-/// 游戏主体
-#[derive(Debug)]
+/// 即将出现的AI蛇，预警结束且出生点仍然安全时放出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiTelegraph {
+    /// 出生点（蛇尾所在格，蛇身向右延伸3格）
+    pub x: i32,
+    pub y: i32,
+    /// 剩余预警时间（秒），出生点被占时会变成负数继续等待
+    pub remaining: f64,
+    /// 蛇头颜色
+    pub color_head: Color,
+    /// 对应出生计划中的第几条
+    spawn: usize,
+}
+
+/// 游戏主体，可整体序列化用于存档，也可以复制一份用于死亡回放
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Game {
     /// 蛇的主体
     snake: Snake,
@@ -45,191 +237,522 @@ pub struct Game {
     food_x: i32,
     /// 食物y坐标
     food_y: i32,
+    /// AI蛇死后留下的残骸果子
+    #[serde(default)]
+    leftover_food: Vec<(i32, i32)>,
     /// 游戏的宽
     width: i32,
     /// 游戏的高
     height: i32,
     /// 游戏是否结束
     game_over: bool,
+    /// 是否因为游戏区被填满而获胜结束
+    #[serde(default)]
+    board_full: bool,
+    /// 第一位玩家最近一次的死因
+    #[serde(default)]
+    death_cause: Option<DeathCause>,
     /// 等待时间
     waiting_time: f64,
+    /// 第一位玩家排队等待的转向，暂停和过关等待时也会先存下来
+    #[serde(default)]
+    turn_queue: VecDeque<Direction>,
+    /// 旧版本回放的规则：转向不排队，立即移动一格
+    #[serde(default)]
+    immediate_turns: bool,
+    /// 正在进行的战役
+    campaign: Campaign,
+    /// 当前关卡的移动周期，每过多长时间进行一次移动
+    moving_period: f64,
     /// 是否暂停
     game_pause: bool,
     /// 当前分数
     score: u32,
     pub level: u32,
     pub level_score: u32,
+    /// 进入当前关卡时的分数，重玩这一关时恢复
+    #[serde(default)]
+    level_start_score: u32,
     pub waiting_next_level: bool,
     /// 障碍物位置
     obstacles: Vec<(i32, i32)>,
+    /// 障碍物每变化一次加一，联机服务器据此判断要不要重发障碍物
+    #[serde(skip)]
+    obstacles_version: u64,
+    /// 障碍物中AI蛇产下的卵
+    #[serde(default)]
+    eggs: Vec<(i32, i32)>,
+    /// 占用网格，由其它字段推导，读档后用 `rebuild_grid` 重建
+    #[serde(skip)]
+    grid: Grid,
     /// AI蛇列表
     pub ai_snakes: Vec<AISnake>,
-    /// AI蛇移动计时器
-    ai_snake_timer: f64,
-    ai_snake_speed: f64,
-    /// AI蛇油滴粒子
+    /// 最近一条出现的AI蛇的编号
+    #[serde(default)]
+    last_ai_id: u32,
+    /// 出生计划中每一条是否已经触发
+    #[serde(default)]
+    ai_schedule_done: Vec<bool>,
+    /// 正在预警、即将出现的AI蛇
+    #[serde(default)]
+    ai_telegraphs: Vec<AiTelegraph>,
+    /// 本关实际进行中的模拟步数，用于定时出生
+    #[serde(default)]
+    level_ticks: u64,
+    /// AI蛇油滴粒子（纯表现，不存档）
+    #[serde(skip)]
     pub ai_oil_particles: Vec<AIOilParticle>,
+    /// 尚未被 `step` 取走的事件
+    #[serde(skip)]
+    events: Vec<GameEvent>,
+    /// 尚未消耗的真实时间，按 `TICK` 切分成模拟步
+    #[serde(skip)]
+    accumulator: f64,
+    /// 已经执行的模拟步数
+    tick: u64,
+    /// 这一局实际进行中的模拟步数（不含暂停、过关等待和死亡后），第1版存档里没有，从0算起
+    #[serde(default)]
+    play_ticks: u64,
+    /// 当前这局的随机种子
+    seed: u64,
+    /// 整个模拟唯一的随机数来源，相同种子+相同输入可复现同一局
+    rng: ChaCha8Rng,
+    /// 从创建开始的全部输入，用于保存回放
+    replay: Replay,
+    /// 双人对战的状态，单人游戏时为 None
+    #[serde(default)]
+    versus: Option<Versus>,
+    /// 第一位玩家以外的玩家
+    #[serde(default)]
+    players: Vec<Player>,
+    /// 是否是联机游戏：玩家随时加入离开，死亡后复活，没有过关目标，游戏不会结束
+    #[serde(default)]
+    online: bool,
+    /// 联机时第一个位置暂时没有人
+    #[serde(default)]
+    first_player_absent: bool,
 }
 
 impl Game {
-    pub const LEVEL_GOAL: u32 = 5;
-    /// 初始化游戏数据
+    /// 初始化游戏数据，使用随机种子和无尽模式
     pub fn new(width: i32, height: i32) -> Game {
+        Game::new_with_seed(width, height, rand::thread_rng().gen())
+    }
+
+    /// 使用指定种子和无尽模式初始化游戏数据
+    pub fn new_with_seed(width: i32, height: i32, seed: u64) -> Game {
+        Game::with_campaign(Campaign::endless(width, height), seed)
+    }
+
+    /// 使用指定战役和种子初始化游戏数据
+    pub fn with_campaign(campaign: Campaign, seed: u64) -> Game {
+        Game::build(campaign, seed, None, false)
+    }
+
+    /// 本地双人对战：第一位玩家用方向键，第二位玩家用 WASD，使用战役第一关的布局，没有AI蛇
+    pub fn new_versus(campaign: Campaign, seed: u64, mode: VersusMode) -> Game {
+        Game::build(campaign, seed, Some(mode), false)
+    }
+
+    /// 联机游戏：一开始没有玩家，用 `join_player` 加入；停留在战役第一关，AI蛇照常出现
+    ///
+    /// 联机的一局无法回放，输入、加入和离开都不会记录进回放，服务器长时间运行也不会越攒越多。
+    pub fn new_online(campaign: Campaign, seed: u64) -> Game {
+        Game::build(campaign, seed, None, true)
+    }
+
+    fn build(campaign: Campaign, seed: u64, versus: Option<VersusMode>, online: bool) -> Game {
+        let first = campaign.level(1);
+        let (width, height) = (first.width, first.height);
         let mut game = Game {
             snake: Snake::new(2, 2),
-            food_exists: true,
-            food_x: 6,
-            food_y: 4,
+            food_exists: false,
+            food_x: 0,
+            food_y: 0,
+            leftover_food: Vec::new(),
             width,
             height,
             game_over: false,
+            board_full: false,
+            death_cause: None,
             waiting_time: 0.0,
+            turn_queue: VecDeque::new(),
+            immediate_turns: false,
+            replay: Replay::new(seed, campaign.clone(), versus),
+            campaign,
+            moving_period: 0.0,
             game_pause: false,
             score: 0,
             level: 1,
             level_score: 0,
+            level_start_score: 0,
             waiting_next_level: false,
             obstacles: Vec::new(),
-            ai_snakes: vec![AISnake::new(width-5, height-5)],
-            ai_snake_timer: 0.0,
-            ai_snake_speed: 0.18,
+            obstacles_version: 0,
+            eggs: Vec::new(),
+            grid: Grid::default(),
+            ai_snakes: Vec::new(),
+            last_ai_id: 0,
+            ai_schedule_done: Vec::new(),
+            ai_telegraphs: Vec::new(),
+            level_ticks: 0,
             ai_oil_particles: Vec::new(),
+            events: Vec::new(),
+            accumulator: 0.0,
+            tick: 0,
+            play_ticks: 0,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            versus: versus.map(|mode| Versus { mode, winner: None }),
+            players: Vec::new(),
+            online,
+            first_player_absent: online,
         };
-        game.generate_obstacles();
+        if versus.is_some() {
+            game.players.push(Player::new(Snake::new(2, height - 3)));
+        }
+        game.load_level();
         game
     }
 
-    /// 对外暴露的控制方法
-    pub fn key_pressed(&mut self, key: Key) {
+    /// 推进一帧模拟：先处理输入，再把 `delta_time` 累积起来按固定步长执行若干模拟步，返回期间发生的事件
+    ///
+    /// 帧来晚时会一次补跑多步，但最多 `MAX_CATCH_UP_TICKS` 步。
+    pub fn step(&mut self, delta_time: f64, inputs: &[Input]) -> Vec<GameEvent> {
+        for &input in inputs {
+            self.handle_input(input);
+        }
+
+        self.accumulator += delta_time;
+        let mut steps = 0;
+        while self.accumulator >= TICK {
+            if steps == MAX_CATCH_UP_TICKS {
+                self.accumulator = 0.0;
+                break;
+            }
+            self.advance_tick();
+            self.accumulator -= TICK;
+            steps += 1;
+        }
+
+        std::mem::take(&mut self.events)
+    }
+
+    /// 处理输入后恰好执行一个模拟步，不使用真实时间，供机器人、回放等无窗口场景使用
+    pub fn tick(&mut self, inputs: &[Input]) -> Vec<GameEvent> {
+        for &input in inputs {
+            self.handle_input(input);
+        }
+        self.advance_tick();
+        std::mem::take(&mut self.events)
+    }
+
+    /// 获取从创建开始记录的回放
+    pub fn get_replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// 获取这一局的游戏时长（秒）
+    pub fn get_play_time(&self) -> f64 {
+        self.play_ticks as f64 * TICK
+    }
+
+    /// 获取这一局实际进行中的模拟步数（不含暂停、过关等待和死亡后）
+    pub fn get_play_ticks(&self) -> u64 {
+        self.play_ticks
+    }
+
+    /// 获取已经执行的模拟步数
+    pub fn get_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// 下一个模拟步玩家蛇是否会移动，机器人在这时决定方向
+    pub fn is_player_move_due(&self) -> bool {
+        !self.game_pause
+            && !self.game_over
+            && !self.waiting_next_level
+            && self.waiting_time + TICK >= self.moving_period
+    }
+
+    /// 执行一个固定步长的模拟步
+    fn advance_tick(&mut self) {
+        self.tick += 1;
+        // 等待进入下一关时冻结模拟
+        if self.waiting_next_level {
+            return;
+        }
+        self.update();
+        self.check_player_ai_collision();
+        self.update_ai_schedule();
+        self.update_ai_snakes();
+    }
+
+    /// 处理一次输入，联机游戏无法回放，不记录输入
+    pub fn handle_input(&mut self, input: Input) {
+        if !self.online {
+            self.replay.record(self.tick, input);
+        }
+
+        if input == Input::NextLevel {
+            if self.waiting_next_level {
+                self.next_level();
+            }
+            return;
+        }
+
         // 输入 R 快速重新游戏
-        if key == Key::R {
+        if input == Input::Restart {
             self.restart()
         }
 
+        // 重玩当前关卡，这一局已经结束或正在等待进入下一关时不起作用
+        if input == Input::RestartLevel {
+            if !self.game_over && !self.waiting_next_level {
+                self.restart_level();
+            }
+            return;
+        }
+
         if self.game_over {
             return;
         }
 
-        let dir = match key {
-            Key::Up => Some(Direction::Up),
-            Key::Down => Some(Direction::Down),
-            Key::Left => Some(Direction::Left),
-            Key::Right => Some(Direction::Right),
-            Key::P => {
+        let (player, dir) = match input {
+            Input::Turn(d) if !self.online => (0, Some(d)),
+            Input::Turn2(d) if self.versus.is_some() => (1, Some(d)),
+            Input::TurnPlayer { player, dir } if self.online && self.is_player_active(player as usize) => {
+                (player as usize, Some(dir))
+            }
+            Input::Turn(_) | Input::Turn2(_) | Input::TurnPlayer { .. } => return,
+            Input::TogglePause => {
                 // 输入 P 暂停/启动游戏
                 self.game_pause = !self.game_pause;
-                None
+                self.events.push(GameEvent::Paused { paused: self.game_pause });
+                (0, None)
             }
-            _ => None,
+            Input::Restart | Input::RestartLevel | Input::NextLevel => (0, None),
         };
 
+        if !self.immediate_turns {
+            if let Some(d) = dir {
+                self.queue_turn(player, d);
+            }
+            return;
+        }
+
         if let Some(d) = dir {
             // 如果输入方向为当前方向的相反方向，不做任何处理
-            if d == self.snake.head_direction().opposite() {
+            if d == self.snake_of(player).head_direction().opposite() {
                 return;
             }
         }
 
         // 如果为有效输入，直接刷新蛇的方向
-        self.update_snake(dir);
+        self.update_snake(player, dir);
+    }
+
+    /// 把转向排进第 `player` 位玩家的队列
+    ///
+    /// 与排在最后的方向（队列为空时是当前方向）相同或相反的转向被丢弃，队列满了也丢弃。
+    fn queue_turn(&mut self, player: usize, dir: Direction) {
+        let current = self.snake_of(player).head_direction();
+        let queue = self.turn_queue_mut(player);
+        let last = queue.back().copied().unwrap_or(current);
+        if dir == last || dir == last.opposite() || queue.len() >= MAX_QUEUED_TURNS {
+            return;
+        }
+        queue.push_back(dir);
+    }
+
+    /// 蛇移动时取出下一个转向；过关或复活后蛇换了方向，已经无效的转向直接跳过
+    fn next_queued_turn(&mut self, player: usize) -> Option<Direction> {
+        let current = self.snake_of(player).head_direction();
+        let queue = self.turn_queue_mut(player);
+        while let Some(dir) = queue.pop_front() {
+            if dir != current && dir != current.opposite() {
+                return Some(dir);
+            }
+        }
+        None
+    }
+
+    fn turn_queue_mut(&mut self, player: usize) -> &mut VecDeque<Direction> {
+        match player {
+            0 => &mut self.turn_queue,
+            _ => &mut self.players[player - 1].turn_queue,
+        }
+    }
+
+    /// 按旧版本回放的规则处理转向：不排队，立即移动一格
+    pub(crate) fn set_immediate_turns(&mut self, immediate: bool) {
+        self.immediate_turns = immediate;
+        self.replay.immediate_turns = immediate;
+    }
+
+    /// 第 `player` 位玩家（从 0 开始）的蛇
+    fn snake_of(&self, player: usize) -> &Snake {
+        match player {
+            0 => &self.snake,
+            _ => &self.players[player - 1].snake,
+        }
+    }
+
+    fn snake_of_mut(&mut self, player: usize) -> &mut Snake {
+        match player {
+            0 => &mut self.snake,
+            _ => &mut self.players[player - 1].snake,
+        }
+    }
+
+    /// 第 `player` 位玩家的分数
+    fn score_of(&self, player: usize) -> u32 {
+        match player {
+            0 => self.score,
+            _ => self.players[player - 1].score,
+        }
+    }
+
+    fn add_score(&mut self, player: usize, points: u32) {
+        match player {
+            0 => self.score += points,
+            _ => self.players[player - 1].score += points,
+        }
+    }
+
+    /// 包括第一位玩家在内的玩家位置数
+    fn player_count(&self) -> usize {
+        1 + self.players.len()
+    }
+
+    /// 第 `player` 个位置是否有人
+    pub fn is_player_active(&self, player: usize) -> bool {
+        match player {
+            0 => !self.first_player_absent,
+            _ => self.players.get(player - 1).is_some_and(|p| p.active),
+        }
+    }
+
+    /// 所有有人的玩家位置
+    fn active_players(&self) -> Vec<usize> {
+        (0..self.player_count()).filter(|&p| self.is_player_active(p)).collect()
     }
 
-    /// 是否吃到了果子
-    fn check_eating(&mut self) {
-        let (head_x, head_y) = self.snake.head_position();
+    /// 占据 `(x, y)` 的玩家
+    fn player_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.active_players()
+            .into_iter()
+            .find(|&p| self.snake_of(p).body().any(|b| (b.x, b.y) == (x, y)))
+    }
+
+    /// 离 `pos` 最近的玩家
+    fn nearest_player(&self, pos: (i32, i32)) -> Option<usize> {
+        self.active_players().into_iter().min_by_key(|&p| {
+            let (x, y) = self.snake_of(p).head_position();
+            (x - pos.0).abs() + (y - pos.1).abs()
+        })
+    }
+
+    /// 是否是多人游戏（对战或联机），没有过关目标
+    fn is_multiplayer(&self) -> bool {
+        self.versus.is_some() || self.online
+    }
+
+    /// 第 `player` 位玩家是否吃到了果子
+    fn check_eating(&mut self, player: usize) {
+        let (head_x, head_y) = self.snake_of(player).head_position();
+        // AI蛇的残骸只加分变长
+        if let Some(i) = self.leftover_food.iter().position(|&p| p == (head_x, head_y)) {
+            self.leftover_food.swap_remove(i);
+            self.grid.set_food(head_x, head_y, false);
+            self.grow_player(player);
+            self.add_score(player, 1);
+            self.events.push(GameEvent::FoodEaten { x: head_x, y: head_y });
+            self.check_versus_goal(player);
+            return;
+        }
         if self.food_exists && self.food_x == head_x && self.food_y == head_y {
-            self.food_exists = false;
-            self.snake.restore_tail();
-            self.score += 1;
+            self.remove_food();
+            self.grow_player(player);
+            self.add_score(player, 1);
+            self.events.push(GameEvent::FoodEaten { x: head_x, y: head_y });
+            // 多人游戏没有关卡目标和产卵
+            if self.is_multiplayer() {
+                self.check_versus_goal(player);
+                return;
+            }
             self.level_score += 1;
             // 玩家吃到食物时AI蛇产卵
-            // 这里不能直接用self，因为需要传递粒子数组，由主循环调用
+            if self.level_def().food.ai_eggs {
+                self.ai_snake_lay_egg_now();
+            }
             // 关卡过关检测
-            if self.level_score >= Self::LEVEL_GOAL {
+            if self.level_score >= self.level_def().goal {
                 self.waiting_next_level = true;
+                self.events.push(GameEvent::LevelCleared { level: self.level });
             }
         }
     }
 
-    /// 对外暴露的游戏绘制
-    pub fn draw(&self, con: &Context, g: &mut G2d, time: f64, glyphs: &mut Glyphs) {
-        self.snake.draw(con, g, time); // 玩家蛇不需要glyphs
-        for ai in &self.ai_snakes {
-            // 残影
-            let mut fade = 0.4;
-            for block in ai.body.iter().skip(1).take(4) {
-                draw_block([0.7, 0.0, 0.0, fade], Shape::Round(12.5, 16), block.x, block.y, con, g);
-                fade *= 0.6;
-            }
-            // 恐怖高光
-            let (hx, hy) = ai.head_position();
-            draw_block([1.0, 0.0, 0.0, 0.7], Shape::Round(6.0, 16), hx, hy, con, g);
-            ai.draw(con, g, time); // AI蛇不需要glyphs
+    /// 玩家蛇变长一格
+    fn grow_player(&mut self, player: usize) {
+        let snake = self.snake_of_mut(player);
+        snake.restore_tail();
+        if let Some((x, y)) = snake.body().last().map(|b| (b.x, b.y)) {
+            self.grid.add_player(x, y);
         }
-        if self.food_exists {
-            // 怪核符号果
-            draw_weirdcore_food(self.food_x, self.food_y, con, g, time, glyphs);
-        }
-        // 绘制AI蛇油滴粒子
-        use piston_window::ellipse;
-        for p in &self.ai_oil_particles {
-            let alpha = ((p.life / p.max_life) as f32).min(1.0) * 0.7;
-            let size = 6.0 * (p.life / p.max_life).max(0.4);
-            ellipse([0.08, 0.08, 0.08, alpha], [p.x - size/2.0, p.y - size/2.0, size, size * 1.2], con.transform, g);
-        }
-        // 绘制障碍物（深灰色）
-        let obstacle_color: Color = [0.2, 0.2, 0.2, 1.0];
-        for &(ox, oy) in &self.obstacles {
-            draw_block(obstacle_color, Shape::Square, ox, oy, con, g);
-        }
-        //上边框
-        draw_rectangle(T_BORDER_COLOR, 0, 0, self.width, 1, con, g);
-        // 下边框
-        draw_rectangle(B_BORDER_COLOR, 0, self.height - 1, self.width, 1, con, g);
-        // 左边框
-        draw_rectangle(L_BORDER_COLOR, 0, 1, 1, self.height - 2, con, g);
-        // 右边框
-        draw_rectangle(
-            R_BORDER_COLOR,
-            self.width - 1,
-            1,
-            1,
-            self.height - 2,
-            con,
-            g,
-        );
-
-        // 如果游戏失败 绘制游戏失败画面
-        if self.game_over {
-            draw_rectangle(GAMEOVER_COLOR, 0, 0, self.width, self.height, con, g);
+    }
+
+    /// 先吃到N个果子的对战中，检查该玩家是否获胜
+    fn check_versus_goal(&mut self, player: usize) {
+        if let Some(Versus { mode: VersusMode::FirstTo { food }, .. }) = self.versus {
+            if self.score_of(player) >= food {
+                let winner = if player == 0 { Winner::PlayerOne } else { Winner::PlayerTwo };
+                self.finish_versus(winner);
+            }
         }
     }
 
-    /// 对外暴露的游戏更新入口
-    pub fn update(&mut self, delta_time: f64) {
+    /// 游戏更新：玩家移动、补充果子、油滴粒子
+    fn update(&mut self) {
         // 如果游戏暂停/结束时，不执行操作
         if self.game_pause || self.game_over {
             return;
         }
+        self.play_ticks += 1;
 
         // 增加游戏的等待时间
-        self.waiting_time += delta_time;
+        self.waiting_time += TICK;
 
         if !self.food_exists {
             self.add_food()
         }
 
-        if self.waiting_time > MOVING_PERIOD {
-            self.update_snake(None)
+        if self.waiting_time >= self.moving_period && self.is_player_active(0) {
+            let dir = self.next_queued_turn(0);
+            self.update_snake(0, dir)
+        }
+
+        // 其他玩家按同样的速度移动；对战中第一位玩家在这一步刚死时照常移动，两人同时死亡算同归于尽
+        let just_lost = self.versus.as_ref().is_some_and(|v| v.winner == Some(Winner::PlayerTwo));
+        for i in 0..self.players.len() {
+            let p = &mut self.players[i];
+            if !p.active {
+                continue;
+            }
+            p.waiting_time += TICK;
+            if p.waiting_time >= self.moving_period && (!self.game_over || just_lost) {
+                let dir = self.next_queued_turn(i + 1);
+                self.update_snake(i + 1, dir);
+            }
         }
 
         // AI蛇油滴粒子生成与更新
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
+        let rng = &mut self.rng;
         for ai in &self.ai_snakes {
             // 头部坐标
             let (hx, hy) = ai.head_position();
-            // 每帧有小概率生成油滴
+            // 每步有小概率生成油滴
             if rng.gen_bool(0.012) {
                 let px = (hx as f64) * 20.0 + 10.0 + rng.gen_range(-3.0..3.0);
                 let py = (hy as f64) * 20.0 + 18.0;
@@ -246,7 +769,7 @@ impl Game {
                 });
             }
             // 身体其他节也有更低概率掉落
-            for (i, block) in ai.body.iter().enumerate().skip(1).take(2) {
+            for block in ai.body.iter().skip(1).take(2) {
                 if rng.gen_bool(0.004) {
                     let px = (block.x as f64) * 20.0 + 10.0 + rng.gen_range(-3.0..3.0);
                     let py = (block.y as f64) * 20.0 + 18.0;
@@ -266,62 +789,325 @@ impl Game {
         }
         // 更新油滴粒子
         for p in &mut self.ai_oil_particles {
-            p.x += p.vx * delta_time * 60.0;
-            p.y += p.vy * delta_time * 60.0;
-            p.life -= delta_time;
+            p.x += p.vx * TICK * 60.0;
+            p.y += p.vy * TICK * 60.0;
+            p.life -= TICK;
         }
         self.ai_oil_particles.retain(|p| p.life > 0.0);
     }
 
-    /// 添加果子
+    /// 添加果子：只放在蛇头能走到的空地上，不与蛇、AI蛇、障碍物重叠
+    ///
+    /// 没有这样的空地时判定游戏区已满，玩家获胜。
     fn add_food(&mut self) {
-        let mut rng = thread_rng();
-
-        let mut new_x = rng.gen_range(1..self.width - 1);
-        let mut new_y = rng.gen_range(1..self.height - 1);
+        let mut reachable = vec![false; (self.width * self.height) as usize];
+        for origin in self.food_origins() {
+            for (r, o) in reachable.iter_mut().zip(self.reachable_from(origin, &[])) {
+                *r |= o;
+            }
+        }
+        let mut candidates = Vec::new();
+        for y in 1..self.height - 1 {
+            for x in 1..self.width - 1 {
+                if reachable[self.cell_index(x, y)] && !self.is_occupied(x, y) && !self.grid.has_food(x, y) {
+                    candidates.push((x, y));
+                }
+            }
+        }
+        if candidates.is_empty() {
+            // 联机时等空地出现再放
+            if self.online {
+                return;
+            }
+            // 对战中梦境已满时按分数决出胜负
+            if self.versus.is_some() {
+                let winner = match self.score.cmp(&self.score_of(1)) {
+                    Ordering::Greater => Winner::PlayerOne,
+                    Ordering::Less => Winner::PlayerTwo,
+                    Ordering::Equal => Winner::Draw,
+                };
+                self.finish_versus(winner);
+                return;
+            }
+            self.board_full = true;
+            self.events.push(GameEvent::BoardFull);
+            self.set_game_over();
+            return;
+        }
+        let (x, y) = candidates[self.rng.gen_range(0..candidates.len())];
+        self.place_food(x, y);
+    }
 
-        while self.snake.over_tail(new_x, new_y) {
-            new_x = rng.gen_range(1..self.width - 1);
-            new_y = rng.gen_range(1..self.height - 1);
+    /// 果子必须能从这些位置走到：所有玩家的蛇头，联机没人时用出生点
+    fn food_origins(&self) -> Vec<(i32, i32)> {
+        let heads: Vec<(i32, i32)> = self
+            .active_players()
+            .into_iter()
+            .map(|p| self.snake_of(p).head_position())
+            .collect();
+        if heads.is_empty() {
+            vec![self.level_def().player_spawn]
+        } else {
+            heads
         }
-        self.food_x = new_x;
-        self.food_y = new_y;
+    }
+
+    /// 在该格放置果子
+    fn place_food(&mut self, x: i32, y: i32) {
+        self.remove_food();
+        self.food_x = x;
+        self.food_y = y;
         self.food_exists = true;
+        self.grid.set_food(x, y, true);
     }
 
-    /// 生成障碍物，数量=10*level，不能与蛇、食物重叠
+    /// 移除果子
+    fn remove_food(&mut self) {
+        if self.food_exists {
+            self.grid.set_food(self.food_x, self.food_y, false);
+        }
+        self.food_exists = false;
+    }
+
+    /// 放置一个障碍物
+    fn add_obstacle(&mut self, x: i32, y: i32) {
+        self.obstacles.push((x, y));
+        self.obstacles_version += 1;
+        self.grid.set_obstacle(x, y, true);
+    }
+
+    /// 该格是否被玩家、AI蛇或障碍物占据
+    fn is_occupied(&self, x: i32, y: i32) -> bool {
+        self.grid.is_occupied(x, y)
+    }
+
+    /// 按其它字段重新生成整张占用网格，换关和读档后调用
+    pub(crate) fn rebuild_grid(&mut self) {
+        self.grid = Grid::new(self.width, self.height);
+        for p in self.active_players() {
+            let body: Vec<(i32, i32)> = self.snake_of(p).body().map(|b| (b.x, b.y)).collect();
+            for (x, y) in body {
+                self.grid.add_player(x, y);
+            }
+        }
+        for ai in &self.ai_snakes {
+            for block in &ai.body {
+                self.grid.add_ai(block.x, block.y);
+            }
+        }
+        for &(x, y) in &self.obstacles {
+            self.grid.set_obstacle(x, y, true);
+        }
+        if self.food_exists {
+            self.grid.set_food(self.food_x, self.food_y, true);
+        }
+        for &(x, y) in &self.leftover_food {
+            self.grid.set_food(x, y, true);
+        }
+    }
+
+    /// 获取占用网格
+    pub fn get_grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// 格子在 `reachable_from` 结果中的下标
+    fn cell_index(&self, x: i32, y: i32) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    /// 从 `start` 出发、只绕开边框和障碍物（以及额外的 `extra` 格）能走到的格子
+    ///
+    /// 蛇身和AI蛇都会移开，不算阻挡。
+    fn reachable_from(&self, start: (i32, i32), extra: &[(i32, i32)]) -> Vec<bool> {
+        let mut seen = vec![false; (self.width * self.height) as usize];
+        let blocked = |x: i32, y: i32| self.grid.is_blocked(x, y) || extra.contains(&(x, y));
+        if blocked(start.0, start.1) {
+            return seen;
+        }
+        let mut queue = std::collections::VecDeque::from([start]);
+        seen[self.cell_index(start.0, start.1)] = true;
+        while let Some((x, y)) = queue.pop_front() {
+            for (nx, ny) in [(x, y - 1), (x, y + 1), (x - 1, y), (x + 1, y)] {
+                if !blocked(nx, ny) && !seen[self.cell_index(nx, ny)] {
+                    seen[self.cell_index(nx, ny)] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        seen
+    }
+
+    /// 在该格放障碍物后，每个蛇头（见 `food_origins`）是否仍能走到原来能走到的其它格子
+    ///
+    /// 果子和其余空地都不会被隔开。
+    fn keeps_connected(&self, cell: (i32, i32)) -> bool {
+        let count = |seen: &[bool]| seen.iter().filter(|&&r| r).count();
+        self.food_origins().into_iter().all(|origin| {
+            if origin == cell {
+                return false;
+            }
+            let before = self.reachable_from(origin, &[]);
+            let lost = before[self.cell_index(cell.0, cell.1)] as usize;
+            count(&self.reachable_from(origin, &[cell])) + lost == count(&before)
+        })
+    }
+
+    /// 按关卡定义生成障碍物，不能与蛇、AI蛇、食物重叠
+    ///
+    /// 随机障碍物逐个放置，会把空地分割成多块的位置直接跳过，保证蛇头能走到每一块空地；
+    /// 蛇头前方几格也保持畅通，开局不会一头撞上。
     fn generate_obstacles(&mut self) {
         use rand::seq::SliceRandom;
-        let mut rng = thread_rng();
+        let count = match &self.level_def().obstacles {
+            ObstacleLayout::None => 0,
+            ObstacleLayout::Random { count } => *count,
+            ObstacleLayout::Scaling { per_level } => per_level * self.level as usize,
+            ObstacleLayout::Map { rows } => {
+                let cells: Vec<(i32, i32)> = rows
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(y, row)| {
+                        row.chars()
+                            .enumerate()
+                            .filter(|&(_, ch)| ch == '#')
+                            .map(move |(x, _)| (x as i32, y as i32))
+                    })
+                    .collect();
+                for (x, y) in cells {
+                    self.add_obstacle(x, y);
+                }
+                return;
+            }
+        };
+        let spawn = self.level_def().player_spawn;
+        let heads = self.food_origins();
+        let head = heads[0];
         let mut positions = Vec::new();
         for x in 1..self.width-1 {
             for y in 1..self.height-1 {
-                // 不与蛇初始位置、食物重叠
-                if (x, y) == (2, 2) || (x, y) == (self.food_x, self.food_y) || self.snake.over_tail(x, y) {
+                // 不与蛇初始位置、食物重叠，也不挡住蛇头前方
+                if (x, y) == spawn
+                    || (self.food_exists && (x, y) == (self.food_x, self.food_y))
+                    || heads.iter().any(|h| y == h.1 && x > h.0 && x <= h.0 + SPAWN_CLEARANCE)
+                    || self.is_occupied(x, y)
+                {
                     continue;
                 }
                 positions.push((x, y));
             }
         }
-        positions.shuffle(&mut rng);
-        let count = (self.level * 10) as usize;
-        self.obstacles = positions.into_iter().take(count).collect();
+        positions.shuffle(&mut self.rng);
+        let mut free = ((self.width - 2) * (self.height - 2)) as usize;
+        for pos in positions {
+            if self.obstacles.len() == count {
+                break;
+            }
+            let reachable = self.reachable_from(head, &[pos]);
+            if reachable.iter().filter(|&&r| r).count() == free - 1 {
+                self.add_obstacle(pos.0, pos.1);
+                free -= 1;
+            }
+        }
     }
 
-    /// 进入下一关，速度翻倍，关卡+1，分数清零，障碍物累积
+    /// 进入下一关，按速度曲线加速，关卡+1，分数清零，布局换成新关卡的
     pub fn next_level(&mut self) {
         self.level += 1;
         self.level_score = 0;
+        self.level_start_score = self.score;
         self.waiting_next_level = false;
+        self.load_level();
+    }
+
+    /// 按当前关卡的定义摆放玩家、AI蛇、果子和障碍物，并设置速度
+    fn load_level(&mut self) {
+        let def = self.level_def().clone();
+        self.width = def.width;
+        self.height = def.height;
         // 玩家和AI蛇长度恢复初始
-        self.snake = Snake::new(2, 2);
-        for ai in &mut self.ai_snakes {
-            *ai = AISnake::new(self.width-5, self.height-5);
+        let (px, py) = def.player_spawn;
+        self.snake = Snake::new(px, py);
+        // 其他玩家先放在各自的出生点，摆好障碍物后再检查
+        for i in 0..self.players.len() {
+            let (x, y) = self.preferred_spawn(i + 1);
+            self.players[i].snake = Snake::new(x, y);
+            self.players[i].waiting_time = 0.0;
+        }
+        self.ai_snakes.clear();
+        self.ai_telegraphs.clear();
+        self.level_ticks = 0;
+        self.obstacles.clear();
+        self.obstacles_version += 1;
+        self.eggs.clear();
+        self.leftover_food.clear();
+        self.food_exists = false;
+        self.rebuild_grid();
+        if let Some((fx, fy)) = def.food.first {
+            self.place_food(fx, fy);
+        }
+        // 开局就在的AI蛇直接放出，其余的等 `update_ai_schedule` 触发；对战没有AI蛇
+        let schedule = if self.versus.is_some() { Vec::new() } else { def.schedule() };
+        self.ai_schedule_done = vec![false; schedule.len()];
+        for (i, spawn) in schedule.iter().enumerate() {
+            if spawn.trigger == SpawnTrigger::Start {
+                self.ai_schedule_done[i] = true;
+                if let Some((x, y)) = self.find_ai_spawn(spawn.at) {
+                    self.spawn_ai(i, spawn, x, y);
+                }
+            }
+        }
+        self.generate_obstacles();
+        // 手绘地图可能压住其他玩家的出生点，几位玩家的出生点也可能重叠，此时另找一个
+        for p in 1..self.player_count() {
+            if !self.is_player_active(p) {
+                continue;
+            }
+            let overlaps = self.snake_of(p).body().any(|b| {
+                self.grid.is_blocked(b.x, b.y)
+                    || (0..p).any(|q| {
+                        self.is_player_active(q) && self.snake_of(q).body().any(|o| (o.x, o.y) == (b.x, b.y))
+                    })
+            });
+            if overlaps {
+                self.respawn_player(p);
+            }
+        }
+        // 手绘地图可能正好压住指定的第一个果子，或把它围在蛇头到不了的地方，此时改为随机放置
+        if self.food_exists {
+            let reachable = self.reachable_from(self.food_origins()[0], &[]);
+            let (fx, fy) = (self.food_x, self.food_y);
+            let inside = fx > 0 && fy > 0 && fx < self.width - 1 && fy < self.height - 1;
+            if !inside || !reachable[self.cell_index(fx, fy)] || self.is_occupied(fx, fy) {
+                self.remove_food();
+            }
         }
-        // 速度翻倍（有上限）
-        self.ai_snake_speed = (self.ai_snake_speed / 2.0).max(0.04);
-        // AI蛇速度范围增加（最大值翻倍，最小值减半）
-        // 这里需要主循环配合修改全局MOVING_PERIOD
+        self.apply_level_speed();
+    }
+
+    /// 当前关卡的定义
+    pub fn level_def(&self) -> &LevelDef {
+        self.campaign.level(self.level)
+    }
+
+    /// 当前关卡的目标分数
+    pub fn get_level_goal(&self) -> u32 {
+        self.level_def().goal
+    }
+
+    /// 获取正在进行的战役
+    pub fn get_campaign(&self) -> &Campaign {
+        &self.campaign
+    }
+
+    /// 按速度曲线设置当前关卡的玩家速度，没有单独指定速度的AI蛇跟随玩家
+    ///
+    /// 只在 `restart` 和 `next_level` 中调用，关卡进行中速度不变。
+    fn apply_level_speed(&mut self) {
+        self.moving_period = self
+            .level_def()
+            .speed
+            .unwrap_or_else(|| self.campaign.speed_curve.moving_period(self.level));
     }
     /// 获取当前关卡
     pub fn get_level(&self) -> u32 {
@@ -332,66 +1118,361 @@ impl Game {
         &self.obstacles
     }
 
+    /// 障碍物的版本号，障碍物有任何变化时都会改变
+    pub fn get_obstacles_version(&self) -> u64 {
+        self.obstacles_version
+    }
+
     /// 判断游戏是否结束
     pub fn is_game_over(&self) -> bool {
         self.game_over
     }
 
+    /// 判断游戏是否因为游戏区被填满而获胜结束
+    pub fn is_board_full(&self) -> bool {
+        self.board_full
+    }
+
+    /// 判断游戏是否暂停
+    pub fn is_paused(&self) -> bool {
+        self.game_pause
+    }
+
+    /// 获取游戏区大小
+    pub fn get_size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// 获取玩家蛇
+    pub fn get_snake(&self) -> &Snake {
+        &self.snake
+    }
+
     /// 获取蛇头坐标
     pub fn get_snake_head(&self) -> (i32, i32) {
         self.snake.head_position()
     }
 
-    /// 检查当前游戏蛇的生存状态，蛇自身碰撞检测、游戏边界碰撞检测
-    fn check_if_snake_alive(&self, dir: Option<Direction>) -> bool {
-        let (next_x, next_y) = self.snake.next_head(dir);
-
-        if self.snake.over_tail(next_x, next_y) {
-            return false;
+    /// 获取果子坐标，不存在时为 None
+    pub fn get_food(&self) -> Option<(i32, i32)> {
+        if self.food_exists {
+            Some((self.food_x, self.food_y))
+        } else {
+            None
         }
+    }
+
+    /// 获取AI蛇留下的残骸果子
+    pub fn get_leftover_food(&self) -> &Vec<(i32, i32)> {
+        &self.leftover_food
+    }
 
-        // 蛇头碰到障碍物判定死亡
-        if self.obstacles.iter().any(|&(ox, oy)| ox == next_x && oy == next_y) {
-            return false;
+    /// 设置战役的速度曲线，从下一次 `restart` 或 `next_level` 开始生效
+    pub fn set_speed_curve(&mut self, curve: SpeedCurve) {
+        self.campaign.speed_curve = curve;
+    }
+
+    /// 获取当前关卡的移动周期
+    pub fn get_moving_period(&self) -> f64 {
+        self.moving_period
+    }
+
+    /// 检查第 `player` 位玩家的蛇这一步会不会死：蛇身碰撞检测（包括另一位玩家）、游戏边界碰撞检测，返回死因
+    fn check_collision(&self, player: usize, dir: Option<Direction>) -> Option<DeathCause> {
+        let snake = self.snake_of(player);
+        let (next_x, next_y) = snake.next_head(dir);
+
+        // 自己的蛇尾这一步会移开，撞上它不算咬到自己
+        let tail = snake.body().last().map(|b| (b.x, b.y));
+        if self.grid.has_player(next_x, next_y) && tail != Some((next_x, next_y)) {
+            let own = snake.body().any(|b| (b.x, b.y) == (next_x, next_y));
+            return Some(if own { DeathCause::SelfBite } else { DeathCause::Player });
         }
 
-        next_x > 0 && next_y > 0 && next_x < self.width - 1 && next_y < self.height - 1
+        // 蛇头碰到边框或障碍物判定死亡
+        if self.grid.is_wall(next_x, next_y) {
+            Some(DeathCause::Wall)
+        } else if self.grid.has_obstacle(next_x, next_y) {
+            // 蛋也记在障碍物里，撞上障碍物时才去蛋的列表里查是哪一种
+            let egg = self.eggs.contains(&(next_x, next_y));
+            Some(if egg { DeathCause::EggObstacle } else { DeathCause::Obstacle })
+        } else {
+            None
+        }
     }
 
-    /// 更新蛇的数据
-    fn update_snake(&mut self, dir: Option<Direction>) {
+    /// 更新第 `player` 位玩家的蛇的数据
+    fn update_snake(&mut self, player: usize, dir: Option<Direction>) {
         if self.game_pause {
             return;
         }
-        if self.check_if_snake_alive(dir) {
-            self.snake.move_forward(dir);
-            self.check_eating();
+        let next = self.snake_of(player).next_head(dir);
+        let other = self
+            .active_players()
+            .into_iter()
+            .find(|&q| q != player && self.snake_of(q).head_position() == next);
+        if let Some(other) = other {
+            // 两位玩家蛇头相撞
+            self.players_head_to_head(player, other);
+        } else if let Some(cause) = self.check_collision(player, dir) {
+            self.player_died(player, cause);
         } else {
-            self.game_over = true;
+            let snake = self.snake_of_mut(player);
+            let tail = snake.body().last().map(|b| (b.x, b.y));
+            snake.move_forward(dir);
+            let (hx, hy) = snake.head_position();
+            self.grid.add_player(hx, hy);
+            if let Some((tx, ty)) = tail {
+                self.grid.remove_player(tx, ty);
+            }
+            self.check_eating(player);
+        }
+        match player {
+            0 => self.waiting_time = 0.0,
+            _ => self.players[player - 1].waiting_time = 0.0,
         }
-        self.waiting_time = 0.0;
     }
 
-    /// 重置游戏
+    /// 第 `player` 位玩家死亡：单人游戏结束；最后存活模式对方获胜；先吃到N个模式和联机时复活
+    fn player_died(&mut self, player: usize, cause: DeathCause) {
+        if player == 0 {
+            self.death_cause = Some(cause);
+        }
+        let pos = self.snake_of(player).head_position();
+        self.events.push(GameEvent::Died { player, cause, pos });
+        match self.versus.as_ref().map(|v| v.mode) {
+            None if !self.online => self.set_game_over(),
+            Some(VersusMode::LastStanding) => {
+                let winner = if player == 0 { Winner::PlayerTwo } else { Winner::PlayerOne };
+                self.finish_versus(winner);
+            }
+            _ => {
+                // 联机时死亡分数清零，先吃到N个的对战保留分数
+                if self.online {
+                    match player {
+                        0 => self.score = 0,
+                        _ => self.players[player - 1].score = 0,
+                    }
+                }
+                self.respawn_player(player);
+            }
+        }
+    }
+
+    /// 两位玩家蛇头相撞：长的一方获胜，一样长同归于尽
+    fn players_head_to_head(&mut self, a: usize, b: usize) {
+        let len_a = self.snake_of(a).body().count();
+        let len_b = self.snake_of(b).body().count();
+        if len_a > len_b {
+            self.player_died(b, DeathCause::Player);
+        } else if len_b > len_a {
+            self.player_died(a, DeathCause::Player);
+        } else if self.versus.as_ref().is_some_and(|v| v.mode == VersusMode::LastStanding) {
+            self.finish_versus(Winner::Draw);
+        } else {
+            self.player_died(a, DeathCause::Player);
+            self.player_died(b, DeathCause::Player);
+        }
+    }
+
+    /// 第 `player` 位玩家的出生点：第一位用关卡的出生点，第二位与第一位上下对称，其余的随机
+    fn preferred_spawn(&self, player: usize) -> (i32, i32) {
+        let (px, py) = self.level_def().player_spawn;
+        match player {
+            0 => (px, py),
+            _ => (px, self.height - 1 - py),
+        }
+    }
+
+    /// 在出生点让第 `player` 位玩家以初始长度复活，出生点被占时随机找一个远离其他玩家的安全位置
+    fn respawn_player(&mut self, player: usize) {
+        let body: Vec<(i32, i32)> = self.snake_of(player).body().map(|b| (b.x, b.y)).collect();
+        for (x, y) in body {
+            self.grid.remove_player(x, y);
+        }
+        let preferred = self.preferred_spawn(player);
+        let avoid: Vec<(i32, i32)> = self
+            .active_players()
+            .into_iter()
+            .filter(|&p| p != player)
+            .map(|p| self.snake_of(p).head_position())
+            .collect();
+        let (x, y) = if player < 2 && self.ai_spawn_cells_free(preferred.0, preferred.1) {
+            preferred
+        } else {
+            let mut candidates = Vec::new();
+            for y in 1..self.height - 1 {
+                for x in 1..self.width - 1 {
+                    if self.is_spawn_safe(x, y, &avoid) {
+                        candidates.push((x, y));
+                    }
+                }
+            }
+            if candidates.is_empty() {
+                preferred
+            } else {
+                candidates[self.rng.gen_range(0..candidates.len())]
+            }
+        };
+        self.turn_queue_mut(player).clear();
+        let snake = self.snake_of_mut(player);
+        *snake = Snake::new(x, y);
+        let body: Vec<(i32, i32)> = snake.body().map(|b| (b.x, b.y)).collect();
+        for (x, y) in body {
+            self.grid.add_player(x, y);
+        }
+    }
+
+    /// 对战分出胜负
+    fn finish_versus(&mut self, mut winner: Winner) {
+        // 同一步里另一位玩家已经死了
+        if self.game_over {
+            winner = Winner::Draw;
+            self.events.retain(|e| !matches!(e, GameEvent::VersusOver { .. } | GameEvent::GameOver { .. }));
+        }
+        if let Some(v) = &mut self.versus {
+            v.winner = Some(winner);
+        }
+        self.game_over = true;
+        let loser = if winner == Winner::PlayerOne { 1 } else { 0 };
+        let (x, y) = self.snake_of(loser).head_position();
+        self.events.push(GameEvent::VersusOver { winner });
+        self.events.push(GameEvent::GameOver { x, y });
+    }
+
+    /// 获取双人对战的状态，单人游戏时为 None
+    pub fn get_versus(&self) -> Option<&Versus> {
+        self.versus.as_ref()
+    }
+
+    /// 获取第一位玩家以外的玩家
+    pub fn get_players(&self) -> &Vec<Player> {
+        &self.players
+    }
+
+    /// 是否是联机游戏
+    pub fn is_online(&self) -> bool {
+        self.online
+    }
+
+    /// 联机时加入一位玩家：优先使用空出来的位置，在安全的地方出生；已满或不是联机游戏时返回 None
+    pub fn join_player(&mut self) -> Option<usize> {
+        if !self.online {
+            return None;
+        }
+        let player = if self.first_player_absent {
+            self.first_player_absent = false;
+            0
+        } else if let Some(i) = self.players.iter().position(|p| !p.active) {
+            self.players[i].active = true;
+            i + 1
+        } else if self.player_count() < MAX_PLAYERS {
+            // 先放在界外，`respawn_player` 会在网格里放好
+            self.players.push(Player::new(Snake::new(-10, -10)));
+            self.player_count() - 1
+        } else {
+            return None;
+        };
+        match player {
+            0 => {
+                self.score = 0;
+                self.waiting_time = 0.0;
+            }
+            _ => {
+                let p = &mut self.players[player - 1];
+                p.score = 0;
+                p.waiting_time = 0.0;
+            }
+        }
+        // 空出来的位置上的蛇已经不在网格里，先加回去再由 `respawn_player` 移走
+        let body: Vec<(i32, i32)> = self.snake_of(player).body().map(|b| (b.x, b.y)).collect();
+        for (x, y) in body {
+            self.grid.add_player(x, y);
+        }
+        self.respawn_player(player);
+        Some(player)
+    }
+
+    /// 联机时第 `player` 位玩家离开，蛇从游戏区移除
+    pub fn leave_player(&mut self, player: usize) {
+        if !self.online || !self.is_player_active(player) {
+            return;
+        }
+        let body: Vec<(i32, i32)> = self.snake_of(player).body().map(|b| (b.x, b.y)).collect();
+        for (x, y) in body {
+            self.grid.remove_player(x, y);
+        }
+        self.turn_queue_mut(player).clear();
+        match player {
+            0 => self.first_player_absent = true,
+            _ => self.players[player - 1].active = false,
+        }
+    }
+
+    /// 标记游戏结束并记录事件
+    fn set_game_over(&mut self) {
+        self.game_over = true;
+        let (x, y) = self.snake.head_position();
+        self.events.push(GameEvent::GameOver { x, y });
+    }
+
+    /// 第一位玩家最近一次的死因，还没死过时为 None
+    pub fn get_death_cause(&self) -> Option<DeathCause> {
+        self.death_cause
+    }
+
+    /// 获取当前这局的随机种子
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// 重置游戏，从当前随机数流派生新种子，重开后的一局与 `with_campaign(同一战役, 新种子)` 完全相同
     pub fn restart(&mut self) {
-        self.snake = Snake::new(2, 2);
-        self.food_exists = true;
-        self.food_x = 6;
-        self.food_y = 4;
+        self.seed = self.rng.gen();
+        self.rng = ChaCha8Rng::seed_from_u64(self.seed);
         self.game_over = false;
+        self.board_full = false;
+        self.death_cause = None;
+        self.last_ai_id = 0;
         self.waiting_time = 0.0;
+        self.accumulator = 0.0;
         self.game_pause = false;
         self.score = 0;
+        self.turn_queue.clear();
+        for p in &mut self.players {
+            p.score = 0;
+            p.turn_queue.clear();
+        }
+        if let Some(v) = &mut self.versus {
+            v.winner = None;
+        }
+        self.play_ticks = 0;
         self.level = 1;
         self.level_score = 0;
+        self.level_start_score = 0;
         self.waiting_next_level = false;
-        self.obstacles.clear();
-        self.generate_obstacles();
-        self.ai_snakes.clear();
-        self.ai_snakes.push(AISnake::new(self.width-5, self.height-5));
-        self.ai_snake_timer = 0.0;
-        self.ai_snake_speed = 0.18;
+        self.load_level();
+        self.ai_oil_particles.clear();
+        self.events.push(GameEvent::Restarted { level_only: false });
+    }
+
+    /// 从头重玩当前关卡：分数回到进入这一关时，布局、AI蛇和果子按关卡定义重新摆放
+    ///
+    /// 随机数流接着往下走，重玩后的这一关和第一次不一定相同。
+    pub fn restart_level(&mut self) {
+        self.waiting_time = 0.0;
+        self.game_pause = false;
+        self.score = self.level_start_score;
+        self.level_score = 0;
+        self.turn_queue.clear();
+        // 多人游戏只有一关，其他玩家的分数都从0开始
+        for p in &mut self.players {
+            p.score = 0;
+            p.turn_queue.clear();
+        }
+        self.load_level();
         self.ai_oil_particles.clear();
+        self.events.push(GameEvent::Restarted { level_only: true });
     }
 
     /// 获取当前分数
@@ -399,126 +1480,434 @@ impl Game {
         self.score
     }
 
-    /// 更新AI蛇
-    pub fn update_ai_snakes(&mut self, ai_snake_speed_min: f64, ai_snake_speed_max: f64) {
-        if self.game_pause { return; }
-        use rand::seq::SliceRandom;
-        let mut rng = rand::thread_rng();
-        self.ai_snake_speed += rng.gen_range(-0.02..0.02);
-        if self.ai_snake_speed < ai_snake_speed_min { self.ai_snake_speed = ai_snake_speed_min; }
-        if self.ai_snake_speed > ai_snake_speed_max { self.ai_snake_speed = ai_snake_speed_max; }
-        self.ai_snake_timer += 0.016;
-        if self.ai_snake_timer < self.ai_snake_speed { return; }
-        self.ai_snake_timer = 0.0;
-        for ai in &mut self.ai_snakes {
-            // 随机游走
-            if rng.gen_bool(0.1) {
-                let dirs = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
-                let dir = *dirs.choose(&mut rng).unwrap();
-                ai.move_forward_wrap(Some(dir), self.width, self.height);
+    /// 更新AI蛇，游戏结束后不再移动，最后的局面和死亡回放保持不变
+    fn update_ai_snakes(&mut self) {
+        if self.game_pause || self.game_over {
+            return;
+        }
+        let default_period = self.moving_period / 2.0;
+        let mut dead = Vec::new();
+        for i in 0..self.ai_snakes.len() {
+            // 每条AI蛇按自己的速度移动
+            let ai = &mut self.ai_snakes[i];
+            ai.move_timer += TICK;
+            if ai.move_timer < ai.speed.unwrap_or(default_period) {
+                continue;
+            }
+            ai.move_timer = 0.0;
+            // 按各自的策略选择方向，后移动的AI蛇能看到先移动的
+            // 联机时盯着最近的玩家，没人时看着自己
+            let ai = &self.ai_snakes[i];
+            let (player_head, player_direction) = match self.nearest_player(ai.head_position()) {
+                Some(p) => (self.snake_of(p).head_position(), self.snake_of(p).head_direction()),
+                None => (ai.head_position(), ai.direction),
+            };
+            let view = AiView {
+                grid: &self.grid,
+                head: ai.head_position(),
+                direction: ai.direction,
+                length: ai.body.len(),
+                player_head,
+                player_direction,
+                food: self.get_food(),
+            };
+            let dir = ai.strategy.controller().choose_direction(&view, &mut self.rng);
+            let next = ai.next_head(Some(dir));
+            let own_tail = ai.body.back().map(|b| (b.x, b.y));
+
+            // 与玩家蛇头相撞，按长度决胜负
+            let head_on = self
+                .active_players()
+                .into_iter()
+                .find(|&p| self.snake_of(p).head_position() == next);
+            if let Some(p) = head_on.filter(|_| !self.game_over) {
+                if self.head_to_head(p, i) {
+                    dead.push(i);
+                }
+                continue;
+            }
+            // 撞上边框、障碍物或别的蛇身就死
+            let (nx, ny) = next;
+            let hits_player = self.grid.has_player(nx, ny);
+            let hits_ai = self.grid.has_ai(nx, ny) && own_tail != Some(next);
+            if self.grid.is_blocked(nx, ny) || hits_player || hits_ai {
+                // 撞上玩家蛇身算这位玩家击杀
+                if let Some(p) = self.player_at(nx, ny).filter(|_| hits_player && !self.game_over) {
+                    self.add_score(p, AI_KILL_SCORE);
+                }
+                self.kill_ai(i);
+                dead.push(i);
+                continue;
+            }
+
+            let ai = &mut self.ai_snakes[i];
+            ai.move_forward(Some(dir));
+            self.grid.add_ai(nx, ny);
+            if let Some((tx, ty)) = own_tail {
+                self.grid.remove_ai(tx, ty);
+            }
+            // 和玩家抢同一个果子，也吃残骸
+            if self.grid.has_food(nx, ny) {
+                if self.food_exists && (self.food_x, self.food_y) == next {
+                    self.remove_food();
+                } else if let Some(j) = self.leftover_food.iter().position(|&p| p == next) {
+                    self.leftover_food.swap_remove(j);
+                    self.grid.set_food(nx, ny, false);
+                }
+                let ai = &mut self.ai_snakes[i];
+                ai.restore_tail();
+                if let Some((tx, ty)) = own_tail {
+                    self.grid.add_ai(tx, ty);
+                }
+                self.events.push(GameEvent::AiSnakeAte { x: nx, y: ny });
+            }
+        }
+        for i in dead.into_iter().rev() {
+            self.ai_snakes.remove(i);
+        }
+    }
+
+    /// 按出生计划触发AI蛇的预警，预警结束后放出
+    fn update_ai_schedule(&mut self) {
+        if self.game_pause || self.game_over {
+            return;
+        }
+        self.level_ticks += 1;
+        if self.ai_schedule_done.iter().all(|&done| done) && self.ai_telegraphs.is_empty() {
+            return;
+        }
+        let schedule = self.level_def().schedule();
+        let elapsed = self.level_ticks as f64 * TICK;
+        for (i, spawn) in schedule.iter().enumerate() {
+            if self.ai_schedule_done.get(i).copied().unwrap_or(true) {
+                continue;
+            }
+            let due = match spawn.trigger {
+                SpawnTrigger::Start => true,
+                SpawnTrigger::Timer { seconds } => elapsed >= seconds,
+                SpawnTrigger::Score { score } => self.level_score >= score,
+            };
+            if !due {
+                continue;
+            }
+            // 暂时找不到安全的出生点就下一步再试
+            if let Some((x, y)) = self.find_ai_spawn(spawn.at) {
+                self.ai_schedule_done[i] = true;
+                self.ai_telegraphs.push(AiTelegraph {
+                    x,
+                    y,
+                    remaining: AI_TELEGRAPH_TIME,
+                    color_head: spawn.color_head.unwrap_or(AI_HEAD_COLOR),
+                    spawn: i,
+                });
+            }
+        }
+        let mut k = 0;
+        while k < self.ai_telegraphs.len() {
+            self.ai_telegraphs[k].remaining -= TICK;
+            let t = &self.ai_telegraphs[k];
+            // 预警结束时出生点被占就继续等
+            if t.remaining <= 0.0 && self.ai_spawn_cells_free(t.x, t.y) {
+                let t = self.ai_telegraphs.remove(k);
+                self.spawn_ai(t.spawn, &schedule[t.spawn], t.x, t.y);
             } else {
-                ai.move_forward_wrap(None, self.width, self.height);
+                k += 1;
             }
         }
     }
 
-    /// 玩家与AI蛇碰撞检测
-    pub fn check_player_ai_collision(&mut self) {
-        let (px, py) = self.snake.head_position();
-        for ai in &self.ai_snakes {
-            for block in &ai.body {
-                if px == block.x && py == block.y {
-                    self.game_over = true;
-                    return;
+    /// 为一条AI蛇找出生点：优先用指定的位置，不安全时随机找一个远离玩家的安全位置
+    fn find_ai_spawn(&mut self, at: Option<(i32, i32)>) -> Option<(i32, i32)> {
+        if let Some((x, y)) = at {
+            if self.is_ai_spawn_safe(x, y) {
+                return Some((x, y));
+            }
+        }
+        let mut candidates = Vec::new();
+        for y in 1..self.height - 1 {
+            for x in 1..self.width - 1 {
+                if self.is_ai_spawn_safe(x, y) {
+                    candidates.push((x, y));
                 }
             }
         }
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(candidates[self.rng.gen_range(0..candidates.len())])
     }
 
-    /// 玩家吃到食物时让所有AI蛇产卵并变长
-    pub fn ai_snake_lay_egg_now(&mut self, particles: &mut Vec<(f64, f64, f64, f64, f64)>) {
+    /// 以 `(x, y)` 为蛇尾的AI蛇能否在这里出生：身体和前方一格都空着，没有其它预警，且离玩家蛇头足够远
+    fn is_ai_spawn_safe(&self, x: i32, y: i32) -> bool {
+        let heads: Vec<(i32, i32)> = self
+            .active_players()
+            .into_iter()
+            .map(|p| self.snake_of(p).head_position())
+            .collect();
+        self.is_spawn_safe(x, y, &heads)
+    }
+
+    /// 以 `(x, y)` 为蛇尾的蛇能否在这里出生，`avoid` 是需要远离的蛇头
+    fn is_spawn_safe(&self, x: i32, y: i32, avoid: &[(i32, i32)]) -> bool {
+        let far = avoid
+            .iter()
+            .all(|&(px, py)| (x + 2 - px).abs() + (y - py).abs() >= AI_SPAWN_MIN_DISTANCE);
+        let reserved = self
+            .ai_telegraphs
+            .iter()
+            .any(|t| t.y == y && (t.x - x).abs() <= 3);
+        far && !reserved && self.ai_spawn_cells_free(x, y) && !self.grid.is_occupied(x + 3, y) && !self.grid.is_blocked(x + 3, y)
+    }
+
+    /// AI蛇身体的三格是否都是空地
+    fn ai_spawn_cells_free(&self, x: i32, y: i32) -> bool {
+        (x..x + 3).all(|cx| {
+            !self.grid.is_blocked(cx, y) && !self.grid.is_occupied(cx, y) && !self.grid.has_food(cx, y)
+        })
+    }
+
+    /// 按出生计划的第 `index` 条在 `(x, y)` 放出一条AI蛇
+    fn spawn_ai(&mut self, index: usize, spawn: &AiSpawn, x: i32, y: i32) {
+        let mut ai = AISnake::new(x, y);
+        self.last_ai_id += 1;
+        ai.id = self.last_ai_id;
+        ai.strategy = spawn
+            .strategy
+            .or(self.level_def().ai_strategy)
+            .unwrap_or_else(|| AiStrategy::for_level(self.level, index));
+        if let Some(color) = spawn.color_head {
+            ai.color_head = color;
+        }
+        if let Some(color) = spawn.color_body {
+            ai.color_body = color;
+        }
+        ai.speed = spawn.speed;
+        for block in &ai.body {
+            self.grid.add_ai(block.x, block.y);
+        }
+        let (hx, hy) = ai.head_position();
+        self.ai_snakes.push(ai);
+        self.events.push(GameEvent::AiSpawned { x: hx, y: hy });
+    }
+
+    /// 获取正在预警、即将出现的AI蛇
+    pub fn get_ai_telegraphs(&self) -> &Vec<AiTelegraph> {
+        &self.ai_telegraphs
+    }
+
+    /// 第 `player` 位玩家的蛇头与第 `index` 条AI蛇的蛇头相撞：长的一方获胜，一样长同归于尽
+    ///
+    /// 返回这条AI蛇是否死亡，调用者负责把它从列表中移除。
+    fn head_to_head(&mut self, player: usize, index: usize) -> bool {
+        let player_len = self.snake_of(player).body().count();
+        let ai_len = self.ai_snakes[index].body.len();
+        if player_len > ai_len {
+            self.add_score(player, AI_KILL_SCORE);
+        }
+        if player_len <= ai_len {
+            let id = self.ai_snakes[index].id;
+            self.player_died(player, DeathCause::AiSnake { id });
+        }
+        if player_len >= ai_len {
+            self.kill_ai(index);
+            return true;
+        }
+        false
+    }
+
+    /// AI蛇死亡：身体移出网格，按关卡规则留下果子或障碍物
+    ///
+    /// 不会把它从 `ai_snakes` 中移除，由调用者处理。
+    fn kill_ai(&mut self, index: usize) {
+        let body: Vec<(i32, i32)> = self.ai_snakes[index].body.iter().map(|b| (b.x, b.y)).collect();
+        for &(x, y) in &body {
+            self.grid.remove_ai(x, y);
+        }
+        let (x, y) = body[0];
+        self.events.push(GameEvent::AiSnakeDied { x, y });
+        let remains = self.level_def().ai_remains;
+        for (x, y) in body {
+            // 残骸不会压在边框、障碍物、蛇身或已有的果子上
+            if self.grid.is_blocked(x, y) || self.grid.is_occupied(x, y) || self.grid.has_food(x, y) {
+                continue;
+            }
+            match remains {
+                AiRemains::Food => {
+                    self.leftover_food.push((x, y));
+                    self.grid.set_food(x, y, true);
+                }
+                // 会把玩家或果子隔开的那几节不留残骸
+                AiRemains::Obstacle if self.keeps_connected((x, y)) => self.add_obstacle(x, y),
+                AiRemains::Obstacle => {}
+            }
+        }
+    }
+
+    /// 玩家与AI蛇碰撞检测：撞上AI蛇身就死，撞上AI蛇头按长度决胜负
+    fn check_player_ai_collision(&mut self) {
+        for player in self.active_players() {
+            if self.game_over {
+                return;
+            }
+            let head = self.snake_of(player).head_position();
+            if !self.grid.has_ai(head.0, head.1) {
+                continue;
+            }
+            match self.ai_snakes.iter().position(|ai| ai.head_position() == head) {
+                Some(i) => {
+                    if self.head_to_head(player, i) {
+                        self.ai_snakes.remove(i);
+                    }
+                }
+                None => {
+                    let id = self
+                        .ai_snakes
+                        .iter()
+                        .find(|ai| ai.body.iter().any(|b| (b.x, b.y) == head))
+                        .map_or(0, |ai| ai.id);
+                    self.player_died(player, DeathCause::AiSnake { id });
+                }
+            }
+        }
+    }
+
+    /// 玩家吃到食物时让所有AI蛇产卵，会把玩家或果子隔开的位置不产卵
+    fn ai_snake_lay_egg_now(&mut self) {
         if self.game_pause { return; }
         let mut to_add = vec![];
-        let mut rng = rand::thread_rng();
-        for ai in &mut self.ai_snakes {
+        for ai in &self.ai_snakes {
             let (hx, hy) = ai.head_position();
             // 避免重复产卵
-            if !self.obstacles.contains(&(hx, hy)) {
+            if !self.grid.has_obstacle(hx, hy) {
                 to_add.push((hx, hy));
-                // 爆炸粒子
-                for _ in 0..18 {
-                    let angle = rng.gen_range(0.0..std::f64::consts::PI * 2.0);
-                    let speed = rng.gen_range(40.0..120.0);
-                    let vx = speed * angle.cos();
-                    let vy = speed * angle.sin();
-                    particles.push((hx as f64 * 20.0 + 10.0, hy as f64 * 20.0 + 10.0, vx, vy, 0.7));
-                }
             }
             // 不再变长
         }
         for (x, y) in to_add {
-            self.obstacles.push((x, y));
+            if !self.keeps_connected((x, y)) {
+                continue;
+            }
+            self.add_obstacle(x, y);
+            self.eggs.push((x, y));
+            self.events.push(GameEvent::EggLaid { x, y });
         }
     }
 }
 
-// 怪核符号池
-const WEIRDCORE_SYMBOLS: [(&str, [f32; 4]); 8] = [
-    ("?", [0.9, 0.9, 0.2, 1.0]),
-    ("!", [1.0, 0.2, 0.2, 1.0]),
-    ("EXIT", [0.7, 0.7, 0.7, 1.0]),
-    ("ERROR", [0.8, 0.2, 0.8, 1.0]),
-    ("鬼", [0.9, 0.0, 0.0, 1.0]),
-    ("眼", [0.7, 0.7, 1.0, 1.0]),
-    ("门", [0.6, 0.6, 0.8, 1.0]),
-    ("手", [0.8, 0.8, 0.8, 1.0]),
-];
-
-/// 怪核符号果绘制
-pub fn draw_weirdcore_food(x: i32, y: i32, con: &Context, g: &mut G2d, time: f64, glyphs: &mut Glyphs) {
-    use piston_window::{ellipse, line, Transformed};
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
-    // 1. 选取符号和主色（随时间变化）
-    let idx = ((time * 0.7).sin().abs() * (WEIRDCORE_SYMBOLS.len() as f64)).floor() as usize % WEIRDCORE_SYMBOLS.len();
-    let (ch, color) = WEIRDCORE_SYMBOLS[idx];
-    // 2. 动态参数
-    let base_x = (x as f64) * 20.0;
-    let base_y = (y as f64) * 20.0;
-    let t = (time * 2.0).sin();
-    let scale = 1.0 + 0.13 * (time * 1.7).sin() + 0.07 * (time * 2.9).cos();
-    let rot = (time * 1.2).sin() * 0.18;
-    let alpha = 0.85 + 0.15 * (time * 3.1).cos();
-    // 3. 光晕/阴影
-    let glow_color = [color[0], color[1], color[2], 0.18 + 0.12 * (time * 2.7).sin().abs() as f32];
-    ellipse(glow_color, [base_x - 8.0, base_y - 8.0, 36.0, 36.0], con.transform, g);
-    // 4. 主体符号
-    let font_size = if ch.len() > 2 { 18 } else { 28 };
-    let symbol_color = [color[0], color[1], color[2], alpha as f32];
-    let transform = con.transform.trans(base_x + 10.0, base_y + 10.0).rot_rad(rot).scale(scale, scale);
-    piston_window::text(symbol_color, font_size, ch, glyphs, transform, g).ok();
-    // 5. 噪点/裂缝
-    for i in 0..rng.gen_range(3..7) {
-        let angle = time * 2.0 + i as f64 * 1.3;
-        let r = 10.0 + 6.0 * (angle * 1.2).sin();
-        let px = base_x + 10.0 + r * (angle).cos();
-        let py = base_y + 10.0 + r * (angle).sin();
-        let dot_alpha = 0.18 + 0.18 * (angle * 1.7).sin().abs() as f32;
-        ellipse([0.08, 0.08, 0.08, dot_alpha], [px, py, 2.0, 2.0], con.transform, g);
-        if i % 2 == 0 {
-            // 裂缝
-            let x2 = px + 2.0 * (angle * 2.1).cos();
-            let y2 = py + 2.0 * (angle * 2.1).sin();
-            line([0.08, 0.08, 0.08, dot_alpha], 1.0, [px, py, x2, y2], con.transform, g);
-        }
-    }
-    // 6. 符号碎片/漂浮点
-    for i in 0..rng.gen_range(2..5) {
-        let angle = time * 1.3 + i as f64 * 2.2;
-        let r = 18.0 + 8.0 * (angle * 1.1).cos();
-        let px = base_x + 10.0 + r * (angle).cos();
-        let py = base_y + 10.0 + r * (angle).sin();
-        let frag_alpha = 0.10 + 0.10 * (angle * 1.9).sin().abs() as f32;
-        ellipse([color[0], color[1], color[2], frag_alpha], [px, py, 2.5, 2.5], con.transform, g);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 10×10、没有障碍物和AI蛇的一关，果子在 (6, 4)
+    fn empty_game() -> Game {
+        let level = LevelDef {
+            obstacles: ObstacleLayout::None,
+            ai_snakes: 0,
+            ..LevelDef::endless(10, 10)
+        };
+        let campaign = Campaign {
+            levels: vec![level],
+            ..Campaign::endless(10, 10)
+        };
+        Game::with_campaign(campaign, 1)
+    }
+
+    #[test]
+    fn same_seed_and_inputs_give_the_same_game() {
+        let run = || {
+            let mut game = Game::new_with_seed(20, 20, 42);
+            for i in 0..2000u32 {
+                let inputs = match i % 240 {
+                    0 => vec![Input::Turn(Direction::Down)],
+                    60 => vec![Input::Turn(Direction::Right)],
+                    120 => vec![Input::Turn(Direction::Up)],
+                    180 => vec![Input::Turn(Direction::Right)],
+                    _ => vec![],
+                };
+                game.tick(&inputs);
+            }
+            serde_json::to_value(&game).unwrap()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn obstacles_never_seal_off_part_of_the_board() {
+        let mut game = empty_game();
+        // x = 7 这一列只在 y = 5 留一个口子
+        for y in (1..9).filter(|&y| y != 5) {
+            game.add_obstacle(7, y);
+        }
+        assert!(!game.keeps_connected((7, 5)));
+        assert!(game.keeps_connected((2, 8)));
+        // 蛇头所在格不能放
+        assert!(!game.keeps_connected(game.get_snake_head()));
+    }
+
+    #[test]
+    fn nothing_moves_after_game_over() {
+        let mut game = Game::new_with_seed(20, 20, 1);
+        while !game.is_game_over() {
+            game.tick(&[]);
+        }
+        assert!(!game.ai_snakes.is_empty());
+        let before = serde_json::to_value(&game.ai_snakes).unwrap();
+        let head = game.get_snake_head();
+        for _ in 0..600 {
+            game.tick(&[]);
+        }
+        assert_eq!(serde_json::to_value(&game.ai_snakes).unwrap(), before);
+        assert_eq!(game.get_snake_head(), head);
+    }
+
+    #[test]
+    fn turn_queue_drops_reversals_repeats_and_overflow() {
+        let mut game = empty_game();
+        // 朝右时向左、向右都不排队
+        game.handle_input(Input::Turn(Direction::Left));
+        game.handle_input(Input::Turn(Direction::Right));
+        assert!(game.turn_queue.is_empty());
+        for dir in [Direction::Up, Direction::Up, Direction::Left, Direction::Down, Direction::Right] {
+            game.handle_input(Input::Turn(dir));
+        }
+        assert_eq!(game.turn_queue.len(), MAX_QUEUED_TURNS);
+        assert_eq!(game.turn_queue, [Direction::Up, Direction::Left, Direction::Down]);
+    }
+
+    #[test]
+    fn queued_turns_are_taken_one_per_move() {
+        let mut game = empty_game();
+        game.handle_input(Input::Turn(Direction::Down));
+        game.handle_input(Input::Turn(Direction::Left));
+        let head = game.get_snake_head();
+        while game.get_snake_head() == head {
+            game.tick(&[]);
+        }
+        assert_eq!(game.get_snake().head_direction(), Direction::Down);
+        assert_eq!(game.turn_queue, [Direction::Left]);
+    }
+
+    #[test]
+    fn online_games_do_not_grow_a_replay() {
+        let mut game = Game::new_online(Campaign::endless(20, 20), 4);
+        let player = game.join_player().unwrap() as u8;
+        for dir in [Direction::Down, Direction::Left, Direction::Up] {
+            game.tick(&[Input::TurnPlayer { player, dir }, Input::TogglePause, Input::TogglePause]);
+        }
+        game.leave_player(player as usize);
+        game.tick(&[]);
+        assert!(game.get_replay().inputs.is_empty());
+    }
+
+    #[test]
+    fn restart_level_restores_the_score_at_level_start() {
+        let mut game = empty_game();
+        game.score = 7;
+        game.level_start_score = 4;
+        game.level_score = 3;
+        game.handle_input(Input::RestartLevel);
+        assert_eq!(game.get_score(), 4);
+        assert_eq!(game.level_score, 0);
+        assert!(game.events.contains(&GameEvent::Restarted { level_only: true }));
     }
 }
@@ -0,0 +1,202 @@
+/// 一个格子里有什么，按 `Grid::occupant` 的优先级从高到低排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Occupant {
+    /// 边框
+    Wall,
+    /// 障碍物（包括AI蛇产下的卵）
+    Obstacle,
+    /// AI蛇的身体
+    AiSnake,
+    /// 玩家蛇的身体
+    Player,
+    /// 果子
+    Food,
+    /// 空地
+    Empty,
+}
+
+/// 单个格子的占用情况，蛇身用计数，允许短暂重叠（AI蛇穿过自己、撞上玩家等）
+#[derive(Debug, Clone, Copy, Default)]
+struct Cell {
+    obstacle: bool,
+    food: bool,
+    player: u16,
+    ai: u16,
+}
+
+/// 游戏区的占用网格，随着蛇的移动增量更新，碰撞、生成和AI探路都用它做 O(1) 查询
+///
+/// 最外一圈是边框，坐标超出游戏区也按边框处理。
+#[derive(Debug, Clone, Default)]
+pub struct Grid {
+    width: i32,
+    height: i32,
+    cells: Vec<Cell>,
+}
+
+impl Grid {
+    /// 创建空网格
+    pub fn new(width: i32, height: i32) -> Grid {
+        Grid {
+            width,
+            height,
+            cells: vec![Cell::default(); (width.max(0) * height.max(0)) as usize],
+        }
+    }
+
+    /// 网格大小
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// 该格是否是边框或在游戏区外
+    pub fn is_wall(&self, x: i32, y: i32) -> bool {
+        x <= 0 || y <= 0 || x >= self.width - 1 || y >= self.height - 1
+    }
+
+    /// 该格是否有障碍物
+    pub fn has_obstacle(&self, x: i32, y: i32) -> bool {
+        self.cell(x, y).is_some_and(|c| c.obstacle)
+    }
+
+    /// 该格是否有果子
+    pub fn has_food(&self, x: i32, y: i32) -> bool {
+        self.cell(x, y).is_some_and(|c| c.food)
+    }
+
+    /// 该格是否有玩家蛇的身体
+    pub fn has_player(&self, x: i32, y: i32) -> bool {
+        self.cell(x, y).is_some_and(|c| c.player > 0)
+    }
+
+    /// 该格是否有AI蛇的身体
+    pub fn has_ai(&self, x: i32, y: i32) -> bool {
+        self.cell(x, y).is_some_and(|c| c.ai > 0)
+    }
+
+    /// 该格是否是走不过去的边框或障碍物
+    pub fn is_blocked(&self, x: i32, y: i32) -> bool {
+        self.is_wall(x, y) || self.has_obstacle(x, y)
+    }
+
+    /// 该格是否被障碍物或任何一条蛇占据（不含边框和果子）
+    pub fn is_occupied(&self, x: i32, y: i32) -> bool {
+        self.cell(x, y).is_some_and(|c| c.obstacle || c.player > 0 || c.ai > 0)
+    }
+
+    /// 该格里优先级最高的东西
+    pub fn occupant(&self, x: i32, y: i32) -> Occupant {
+        if self.is_wall(x, y) {
+            return Occupant::Wall;
+        }
+        match self.cell(x, y) {
+            Some(c) if c.obstacle => Occupant::Obstacle,
+            Some(c) if c.ai > 0 => Occupant::AiSnake,
+            Some(c) if c.player > 0 => Occupant::Player,
+            Some(c) if c.food => Occupant::Food,
+            _ => Occupant::Empty,
+        }
+    }
+
+    /// 放置/移除障碍物
+    pub fn set_obstacle(&mut self, x: i32, y: i32, obstacle: bool) {
+        if let Some(c) = self.cell_mut(x, y) {
+            c.obstacle = obstacle;
+        }
+    }
+
+    /// 放置/移除果子
+    pub fn set_food(&mut self, x: i32, y: i32, food: bool) {
+        if let Some(c) = self.cell_mut(x, y) {
+            c.food = food;
+        }
+    }
+
+    /// 玩家蛇身进入该格
+    pub fn add_player(&mut self, x: i32, y: i32) {
+        if let Some(c) = self.cell_mut(x, y) {
+            c.player += 1;
+        }
+    }
+
+    /// 玩家蛇身离开该格
+    pub fn remove_player(&mut self, x: i32, y: i32) {
+        if let Some(c) = self.cell_mut(x, y) {
+            c.player = c.player.saturating_sub(1);
+        }
+    }
+
+    /// AI蛇身进入该格
+    pub fn add_ai(&mut self, x: i32, y: i32) {
+        if let Some(c) = self.cell_mut(x, y) {
+            c.ai += 1;
+        }
+    }
+
+    /// AI蛇身离开该格
+    pub fn remove_ai(&mut self, x: i32, y: i32) {
+        if let Some(c) = self.cell_mut(x, y) {
+            c.ai = c.ai.saturating_sub(1);
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            None
+        } else {
+            Some((y * self.width + x) as usize)
+        }
+    }
+
+    fn cell(&self, x: i32, y: i32) -> Option<&Cell> {
+        self.index(x, y).map(|i| &self.cells[i])
+    }
+
+    fn cell_mut(&mut self, x: i32, y: i32) -> Option<&mut Cell> {
+        self.index(x, y).map(move |i| &mut self.cells[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snake_counters_allow_overlap() {
+        let mut grid = Grid::new(10, 10);
+        grid.add_player(3, 3);
+        grid.add_player(3, 3);
+        grid.remove_player(3, 3);
+        assert!(grid.has_player(3, 3));
+        grid.remove_player(3, 3);
+        assert!(!grid.has_player(3, 3));
+        // 多移除一次不会下溢
+        grid.remove_player(3, 3);
+        grid.add_player(3, 3);
+        assert!(grid.has_player(3, 3));
+
+        grid.add_ai(4, 4);
+        assert!(grid.has_ai(4, 4) && grid.is_occupied(4, 4) && !grid.is_blocked(4, 4));
+        grid.remove_ai(4, 4);
+        assert!(!grid.is_occupied(4, 4));
+    }
+
+    #[test]
+    fn occupant_follows_priority() {
+        let mut grid = Grid::new(10, 10);
+        grid.set_food(5, 5, true);
+        assert_eq!(grid.occupant(5, 5), Occupant::Food);
+        assert!(!grid.is_occupied(5, 5));
+        grid.add_player(5, 5);
+        assert_eq!(grid.occupant(5, 5), Occupant::Player);
+        grid.add_ai(5, 5);
+        assert_eq!(grid.occupant(5, 5), Occupant::AiSnake);
+        grid.set_obstacle(5, 5, true);
+        assert_eq!(grid.occupant(5, 5), Occupant::Obstacle);
+        assert!(grid.is_blocked(5, 5));
+        grid.set_obstacle(5, 5, false);
+        grid.remove_ai(5, 5);
+        grid.remove_player(5, 5);
+        assert_eq!(grid.occupant(5, 5), Occupant::Food);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::snake_game::game::Game;
+use crate::snake_game::save::write_atomic;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 排行榜保留的条目数
+pub const MAX_ENTRIES: usize = 10;
+/// 排行榜文件格式版本
+const TABLE_VERSION: u32 = 1;
+
+/// 排行榜中的一条记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    /// 玩家名字
+    pub name: String,
+    /// 分数
+    pub score: u32,
+    /// 到达的关卡
+    pub level: u32,
+    /// 死亡时蛇的长度
+    pub length: usize,
+    /// 游戏时长（秒）
+    pub time_played: f64,
+    /// 记录时间（Unix 秒）
+    pub date: u64,
+    /// 这一局的随机种子
+    pub seed: u64,
+}
+
+impl HighScoreEntry {
+    /// 用一局游戏的当前结果生成记录
+    pub fn from_game(game: &Game, name: String) -> HighScoreEntry {
+        HighScoreEntry {
+            name,
+            score: game.get_score(),
+            level: game.get_level(),
+            length: game.get_snake().body().count(),
+            time_played: game.get_play_time(),
+            date: now_unix(),
+            seed: game.get_seed(),
+        }
+    }
+}
+
+/// 本地排行榜，按分数从高到低排列，最多 `MAX_ENTRIES` 条
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    version: u32,
+    entries: Vec<HighScoreEntry>,
+}
+
+impl HighScores {
+    /// 读取排行榜，文件不存在时返回空表
+    pub fn load(path: &Path) -> io::Result<HighScores> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(HighScores::default()),
+            Err(e) => return Err(e),
+        };
+        let table: HighScores = serde_json::from_str(&text)?;
+        if table.version > TABLE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("排行榜版本 {} 比当前游戏支持的版本 {} 新", table.version, TABLE_VERSION),
+            ));
+        }
+        Ok(table)
+    }
+
+    /// 原子地写入排行榜，写到一半崩溃不会损坏已有的表
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let table = HighScores {
+            version: TABLE_VERSION,
+            entries: self.entries.clone(),
+        };
+        write_atomic(path, serde_json::to_string_pretty(&table)?.as_bytes())
+    }
+
+    /// 全部记录，从高到低
+    pub fn entries(&self) -> &[HighScoreEntry] {
+        &self.entries
+    }
+
+    /// 该分数能否进入排行榜
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_ENTRIES
+                || self.entries.last().is_none_or(|e| score > e.score))
+    }
+
+    /// 插入一条记录，返回名次（从0开始），没能上榜时返回 None
+    pub fn insert(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+        // 同分时先到者在前
+        let rank = self.entries.iter().position(|e| entry.score > e.score).unwrap_or(self.entries.len());
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+}
+
+/// 当前 Unix 时间（秒）
+pub fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 把 Unix 秒格式化为 UTC 日期 `YYYY-MM-DD`
+pub fn format_date(unix_secs: u64) -> String {
+    // Howard Hinnant 的 civil_from_days 算法
+    let z = (unix_secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, score: u32) -> HighScoreEntry {
+        HighScoreEntry {
+            name: name.to_string(),
+            score,
+            level: 1,
+            length: 3,
+            time_played: 0.0,
+            date: 0,
+            seed: 0,
+        }
+    }
+
+    fn names(table: &HighScores) -> Vec<&str> {
+        table.entries().iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn entries_stay_sorted_and_ties_keep_the_earlier_one_first() {
+        let mut table = HighScores::default();
+        assert_eq!(table.insert(entry("a", 5)), Some(0));
+        assert_eq!(table.insert(entry("b", 9)), Some(0));
+        assert_eq!(table.insert(entry("c", 5)), Some(2));
+        assert_eq!(table.insert(entry("d", 7)), Some(1));
+        assert_eq!(names(&table), ["b", "d", "a", "c"]);
+    }
+
+    #[test]
+    fn full_table_only_takes_higher_scores() {
+        let mut table = HighScores::default();
+        for score in 1..=MAX_ENTRIES as u32 {
+            table.insert(entry("x", score * 10));
+        }
+        assert_eq!(table.entries().len(), MAX_ENTRIES);
+        // 与最后一名同分不能上榜
+        assert!(!table.qualifies(10));
+        assert_eq!(table.insert(entry("tie", 10)), None);
+        assert_eq!(table.insert(entry("new", 15)), Some(MAX_ENTRIES - 1));
+        assert_eq!(table.entries().len(), MAX_ENTRIES);
+        assert_eq!(table.entries().last().unwrap().score, 15);
+        // 0 分从不上榜
+        assert!(!HighScores::default().qualifies(0));
+    }
+}
@@ -0,0 +1,205 @@
+use crate::snake_game::game::{Game, GameEvent, Input, TICK};
+use std::collections::VecDeque;
+
+/// 每隔多少个实际进行的模拟步留一份局面的副本
+const CHECKPOINT_INTERVAL: u64 = 60;
+
+/// 死亡回放倒回去的实际进行的模拟步数（约 2 秒），暂停和过关等待不算
+const KILLCAM_TICKS: u64 = 120;
+
+/// 最多保留的副本数，足够覆盖 `KILLCAM_TICKS`
+const MAX_CHECKPOINTS: usize = (KILLCAM_TICKS / CHECKPOINT_INTERVAL) as usize + 2;
+
+/// 慢动作的播放倍率
+pub const KILLCAM_SPEED: f64 = 0.3;
+
+/// 死亡后定格的时间（秒）
+const KILLCAM_HOLD: f64 = 0.8;
+
+/// 一份局面副本，以及当时已经记录的输入条数
+#[derive(Debug)]
+struct Checkpoint {
+    game: Game,
+    inputs: usize,
+}
+
+/// 死亡回放的记录器：每秒复制一份局面，死后从几秒前的副本按回放记录的输入重新模拟
+///
+/// 模拟是确定的，重新模拟出的就是刚才发生的那几秒。
+#[derive(Debug, Default)]
+pub struct KillCam {
+    checkpoints: VecDeque<Checkpoint>,
+}
+
+impl KillCam {
+    pub fn new() -> KillCam {
+        KillCam::default()
+    }
+
+    /// 每帧模拟之后调用，`events` 是这一帧发生的事件；到时间就留一份副本
+    pub fn observe(&mut self, game: &Game, events: &[GameEvent]) {
+        // 重新开始后，之前的副本不属于这一次
+        if events.iter().any(|e| matches!(e, GameEvent::Restarted { .. })) {
+            self.clear();
+        }
+        if game.is_game_over() {
+            return;
+        }
+        // 模拟步倒退说明换了一局
+        if self.checkpoints.back().is_some_and(|last| game.get_tick() < last.game.get_tick()) {
+            self.clear();
+        }
+        let due = match self.checkpoints.back() {
+            Some(last) => game.get_play_ticks() >= last.game.get_play_ticks() + CHECKPOINT_INTERVAL,
+            None => true,
+        };
+        if due {
+            self.checkpoints.push_back(Checkpoint {
+                game: game.clone(),
+                inputs: game.get_replay().inputs.len(),
+            });
+            if self.checkpoints.len() > MAX_CHECKPOINTS {
+                self.checkpoints.pop_front();
+            }
+        }
+    }
+
+    /// 丢掉所有副本
+    pub fn clear(&mut self) {
+        self.checkpoints.clear();
+    }
+
+    /// 从死亡前约 `KILLCAM_TICKS` 的副本开始回放，`game` 是刚刚结束的这一局；没有副本时返回 None
+    pub fn start(&self, game: &Game) -> Option<KillCamPlayback> {
+        let target = game.get_play_ticks().saturating_sub(KILLCAM_TICKS);
+        let checkpoint = self
+            .checkpoints
+            .iter()
+            .rev()
+            .find(|c| c.game.get_play_ticks() <= target)
+            .or_else(|| self.checkpoints.front())?;
+        Some(KillCamPlayback {
+            game: checkpoint.game.clone(),
+            inputs: game.get_replay().inputs[checkpoint.inputs..].to_vec(),
+            cursor: 0,
+            end_tick: game.get_tick(),
+            accumulator: 0.0,
+            hold: 0.0,
+        })
+    }
+}
+
+/// 正在播放的死亡回放
+#[derive(Debug)]
+pub struct KillCamPlayback {
+    game: Game,
+    inputs: Vec<(u64, Input)>,
+    cursor: usize,
+    /// 死亡时的模拟步
+    end_tick: u64,
+    accumulator: f64,
+    /// 重现死亡后已经定格的时间
+    hold: f64,
+}
+
+impl KillCamPlayback {
+    /// 按慢动作推进，返回重新模拟时发生的事件；暂停和过关等待的模拟步一带而过
+    pub fn advance(&mut self, delta_time: f64) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.reached_end() {
+            self.hold += delta_time;
+            return events;
+        }
+        self.accumulator += delta_time * KILLCAM_SPEED;
+        while self.accumulator >= TICK && !self.reached_end() {
+            let mut inputs = Vec::new();
+            while let Some(&(tick, input)) = self.inputs.get(self.cursor) {
+                if tick > self.game.get_tick() {
+                    break;
+                }
+                inputs.push(input);
+                self.cursor += 1;
+            }
+            let play_ticks = self.game.get_play_ticks();
+            events.extend(self.game.tick(&inputs));
+            if self.game.get_play_ticks() > play_ticks {
+                self.accumulator -= TICK;
+            }
+        }
+        events
+    }
+
+    /// 回放中的局面
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// 重现了死亡并定格了一会儿
+    pub fn is_finished(&self) -> bool {
+        self.reached_end() && self.hold >= KILLCAM_HOLD
+    }
+
+    fn reached_end(&self) -> bool {
+        self.game.is_game_over() || self.game.get_tick() >= self.end_tick
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 不转向地一直走到撞死，每步都交给 `cam`
+    fn play_until_death(game: &mut Game, cam: &mut KillCam) {
+        while !game.is_game_over() {
+            let events = game.tick(&[]);
+            cam.observe(game, &events);
+        }
+    }
+
+    #[test]
+    fn restart_drops_checkpoints_from_the_previous_run() {
+        let mut game = Game::new_with_seed(40, 40, 2);
+        let mut cam = KillCam::new();
+        play_until_death(&mut game, &mut cam);
+        let restart_tick = game.get_tick();
+        let events = game.tick(&[Input::Restart]);
+        cam.observe(&game, &events);
+        assert!(cam.checkpoints.iter().all(|c| c.game.get_tick() > restart_tick));
+        play_until_death(&mut game, &mut cam);
+        let playback = cam.start(&game).unwrap();
+        assert!(playback.game().get_tick() > restart_tick);
+    }
+
+    #[test]
+    fn paused_ticks_do_not_count_toward_the_window() {
+        let mut game = Game::new_with_seed(40, 40, 2);
+        let mut cam = KillCam::new();
+        // 死前不久暂停很久
+        for _ in 0..300 {
+            let events = game.tick(&[]);
+            cam.observe(&game, &events);
+        }
+        let events = game.tick(&[Input::TogglePause]);
+        cam.observe(&game, &events);
+        for _ in 0..1000 {
+            let events = game.tick(&[]);
+            cam.observe(&game, &events);
+        }
+        let events = game.tick(&[Input::TogglePause]);
+        cam.observe(&game, &events);
+        play_until_death(&mut game, &mut cam);
+        assert!(game.get_play_ticks() > KILLCAM_TICKS + CHECKPOINT_INTERVAL);
+
+        let mut playback = cam.start(&game).unwrap();
+        let rewound = game.get_play_ticks() - playback.game().get_play_ticks();
+        assert!((KILLCAM_TICKS..KILLCAM_TICKS + CHECKPOINT_INTERVAL).contains(&rewound));
+        assert!(game.get_tick() - playback.game().get_tick() > 1000);
+        // 慢动作按实际进行的模拟步计时
+        let frames = (rewound as f64 / KILLCAM_SPEED).ceil() as usize;
+        for _ in 0..frames + 1 {
+            playback.advance(TICK);
+        }
+        assert!(playback.game().is_game_over());
+        assert_eq!(playback.game().get_tick(), game.get_tick());
+    }
+}
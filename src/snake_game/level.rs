@@ -0,0 +1,370 @@
+use crate::snake_game::speed::SpeedCurve;
+use crate::snake_snake::ai::AiStrategy;
+use crate::snake_snake::snake::Color;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 障碍物布局：手工绘制的地图，或选择一种生成方式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ObstacleLayout {
+    /// 没有障碍物
+    None,
+    /// 随机放置固定数量
+    Random { count: usize },
+    /// 随机放置 `per_level * 关卡` 个
+    Scaling { per_level: usize },
+    /// 手工绘制，每行一个字符串，`#` 为障碍物，其它字符为空地
+    Map { rows: Vec<String> },
+}
+
+/// 果子规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoodRules {
+    /// 开局第一个果子的位置，不填则随机
+    #[serde(default)]
+    pub first: Option<(i32, i32)>,
+    /// 玩家吃到果子时AI蛇是否在原地产卵（变为障碍物）
+    #[serde(default = "default_true")]
+    pub ai_eggs: bool,
+}
+
+impl Default for FoodRules {
+    fn default() -> FoodRules {
+        FoodRules {
+            first: None,
+            ai_eggs: true,
+        }
+    }
+}
+
+/// AI蛇死后留下的残骸
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AiRemains {
+    /// 整条身体变成果子，吃到加分变长，但不计入过关目标
+    #[default]
+    Food,
+    /// 整条身体变成障碍物
+    Obstacle,
+}
+
+/// AI蛇何时出现
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SpawnTrigger {
+    /// 开局就在，不预警
+    #[default]
+    Start,
+    /// 开局后经过 `seconds` 秒（只算实际进行的时间）
+    Timer { seconds: f64 },
+    /// 本关分数达到 `score`
+    Score { score: u32 },
+}
+
+/// 出生计划中的一条AI蛇
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct AiSpawn {
+    /// 何时出现
+    #[serde(default)]
+    pub trigger: SpawnTrigger,
+    /// 出生点（蛇尾所在格，蛇身向右延伸3格、朝右），不填或不安全时随机找一个远离玩家的安全位置
+    #[serde(default)]
+    pub at: Option<(i32, i32)>,
+    /// 行为策略，不填则使用关卡的 `ai_strategy` 或按关卡数自动选择
+    #[serde(default)]
+    pub strategy: Option<AiStrategy>,
+    /// 蛇头颜色
+    #[serde(default)]
+    pub color_head: Option<Color>,
+    /// 蛇身颜色
+    #[serde(default)]
+    pub color_body: Option<Color>,
+    /// 移动周期（秒），不填则是玩家的一半
+    #[serde(default)]
+    pub speed: Option<f64>,
+}
+
+/// 一关的定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelDef {
+    /// 关卡名
+    #[serde(default)]
+    pub name: String,
+    /// 游戏区的宽（含边框）
+    pub width: i32,
+    /// 游戏区的高（含边框）
+    pub height: i32,
+    /// 障碍物布局
+    pub obstacles: ObstacleLayout,
+    /// 过关需要的分数
+    pub goal: u32,
+    /// 移动周期（秒），不填则使用战役的速度曲线
+    #[serde(default)]
+    pub speed: Option<f64>,
+    /// AI蛇数量，`ai_schedule` 为空时使用
+    #[serde(default)]
+    pub ai_snakes: usize,
+    /// 玩家出生点（蛇尾所在格，蛇身向右延伸3格、朝右）
+    pub player_spawn: (i32, i32),
+    /// AI蛇出生点，数量不足时循环使用，`ai_schedule` 为空时使用
+    #[serde(default)]
+    pub ai_spawns: Vec<(i32, i32)>,
+    /// AI蛇的出生计划，不填则开局放出 `ai_snakes` 条
+    #[serde(default)]
+    pub ai_schedule: Vec<AiSpawn>,
+    /// AI蛇的行为策略，不填则按关卡数自动选择
+    #[serde(default)]
+    pub ai_strategy: Option<AiStrategy>,
+    /// AI蛇死后留下的残骸
+    #[serde(default)]
+    pub ai_remains: AiRemains,
+    /// 果子规则
+    #[serde(default)]
+    pub food: FoodRules,
+}
+
+impl LevelDef {
+    /// 原来的隐式关卡：每关 `10 * 关卡` 个随机障碍物，5 分过关，一条AI蛇
+    pub fn endless(width: i32, height: i32) -> LevelDef {
+        LevelDef {
+            name: "无尽噩梦".to_string(),
+            width,
+            height,
+            obstacles: ObstacleLayout::Scaling { per_level: 10 },
+            goal: 5,
+            speed: None,
+            ai_snakes: 1,
+            player_spawn: (2, 2),
+            ai_spawns: vec![(width - 5, height - 5)],
+            ai_schedule: Vec::new(),
+            ai_strategy: None,
+            ai_remains: AiRemains::Food,
+            food: FoodRules {
+                first: Some((6, 4)),
+                ai_eggs: true,
+            },
+        }
+    }
+
+    /// 本关AI蛇的出生计划，没有写 `ai_schedule` 时由 `ai_snakes` 和 `ai_spawns` 生成
+    pub fn schedule(&self) -> Vec<AiSpawn> {
+        if !self.ai_schedule.is_empty() {
+            return self.ai_schedule.clone();
+        }
+        (0..self.ai_snakes)
+            .map(|i| AiSpawn {
+                at: Some(if self.ai_spawns.is_empty() {
+                    (self.width - 5, self.height - 5)
+                } else {
+                    self.ai_spawns[i % self.ai_spawns.len()]
+                }),
+                ..AiSpawn::default()
+            })
+            .collect()
+    }
+
+    /// 检查定义是否合法
+    fn validate(&self) -> Result<(), String> {
+        if self.width < 8 || self.height < 8 {
+            return Err(format!("关卡「{}」的游戏区太小", self.name));
+        }
+        if self.goal == 0 {
+            return Err(format!("关卡「{}」的目标分数必须大于0", self.name));
+        }
+        let positive = |speed: Option<f64>| speed.is_none_or(|s| s.is_finite() && s > 0.0);
+        if !positive(self.speed) || !self.ai_schedule.iter().all(|spawn| positive(spawn.speed)) {
+            return Err(format!("关卡「{}」的移动周期必须大于0", self.name));
+        }
+        let inside = |(x, y): (i32, i32)| x > 0 && y > 0 && x < self.width - 1 && y < self.height - 1;
+        let (px, py) = self.player_spawn;
+        if !inside((px, py)) || !inside((px + 2, py)) {
+            return Err(format!("关卡「{}」的玩家出生点在游戏区外", self.name));
+        }
+        // AI蛇和玩家一样以出生点为蛇尾，向右占三格
+        let ai_spawns: Vec<(i32, i32)> = self.schedule().iter().filter_map(|spawn| spawn.at).collect();
+        if !ai_spawns.iter().all(|&(x, y)| inside((x, y)) && inside((x + 2, y))) {
+            return Err(format!("关卡「{}」的AI蛇出生点在游戏区外", self.name));
+        }
+        if let ObstacleLayout::Map { rows } = &self.obstacles {
+            if rows.len() > self.height as usize || rows.iter().any(|r| r.chars().count() > self.width as usize) {
+                return Err(format!("关卡「{}」的地图超出游戏区", self.name));
+            }
+            let is_wall = |x: i32, y: i32| {
+                rows.get(y as usize).and_then(|r| r.chars().nth(x as usize)) == Some('#')
+            };
+            if (px..=px + 2).any(|x| is_wall(x, py)) {
+                return Err(format!("关卡「{}」的地图压住了玩家出生点", self.name));
+            }
+            if ai_spawns.iter().any(|&(ax, ay)| (ax..=ax + 2).any(|x| is_wall(x, ay))) {
+                return Err(format!("关卡「{}」的地图压住了AI蛇出生点", self.name));
+            }
+            // 手绘地图不能把空地分成几块，否则果子可能刷在蛇走不到的地方
+            let open = |(x, y): (i32, i32)| inside((x, y)) && !is_wall(x, y);
+            let mut seen = vec![false; (self.width * self.height) as usize];
+            let index = |(x, y): (i32, i32)| (y * self.width + x) as usize;
+            let mut stack = vec![(px, py)];
+            seen[index((px, py))] = true;
+            let mut reached = 0;
+            while let Some((x, y)) = stack.pop() {
+                reached += 1;
+                for next in [(x, y - 1), (x, y + 1), (x - 1, y), (x + 1, y)] {
+                    if open(next) && !seen[index(next)] {
+                        seen[index(next)] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+            let total = (1..self.height - 1)
+                .flat_map(|y| (1..self.width - 1).map(move |x| (x, y)))
+                .filter(|&cell| open(cell))
+                .count();
+            if reached != total {
+                return Err(format!("关卡「{}」的地图把游戏区隔成了几块", self.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 战役清单文件的内容
+#[derive(Debug, Deserialize)]
+struct CampaignManifest {
+    name: String,
+    #[serde(default)]
+    speed_curve: SpeedCurve,
+    /// 关卡文件，路径相对于清单文件
+    levels: Vec<String>,
+}
+
+/// 战役：按顺序排列的关卡，打完最后一关后一直重复最后一关
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Campaign {
+    pub name: String,
+    /// 没有指定 `speed` 的关卡使用的速度曲线
+    pub speed_curve: SpeedCurve,
+    pub levels: Vec<LevelDef>,
+}
+
+impl Campaign {
+    /// 原来的无尽模式
+    pub fn endless(width: i32, height: i32) -> Campaign {
+        Campaign {
+            name: "无尽噩梦".to_string(),
+            speed_curve: SpeedCurve::default(),
+            levels: vec![LevelDef::endless(width, height)],
+        }
+    }
+
+    /// 读取战役清单及其列出的关卡文件
+    pub fn load(manifest_path: &Path) -> io::Result<Campaign> {
+        let manifest: CampaignManifest = parse_toml(manifest_path)?;
+        let dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+        let mut levels = Vec::new();
+        for file in &manifest.levels {
+            levels.push(parse_toml(&dir.join(file))?);
+        }
+        let campaign = Campaign {
+            name: manifest.name,
+            speed_curve: manifest.speed_curve,
+            levels,
+        };
+        campaign.validate().map_err(invalid_data)?;
+        Ok(campaign)
+    }
+
+    /// 检查战役至少有一关，且每一关的定义都合法
+    pub fn validate(&self) -> Result<(), String> {
+        if self.levels.is_empty() {
+            return Err(format!("战役「{}」没有关卡", self.name));
+        }
+        self.levels.iter().try_for_each(LevelDef::validate)
+    }
+
+    /// 第 `level` 关（从1开始）的定义
+    pub fn level(&self, level: u32) -> &LevelDef {
+        let idx = (level.max(1) as usize - 1).min(self.levels.len() - 1);
+        &self.levels[idx]
+    }
+}
+
+fn parse_toml<T: serde::de::DeserializeOwned>(path: &Path) -> io::Result<T> {
+    let text = fs::read_to_string(path)?;
+    toml::from_str(&text).map_err(|e| invalid_data(format!("{}: {}", path.display(), e)))
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_map(rows: &[&str]) -> LevelDef {
+        LevelDef {
+            obstacles: ObstacleLayout::Map {
+                rows: rows.iter().map(|r| r.to_string()).collect(),
+            },
+            ..LevelDef::endless(10, 10)
+        }
+    }
+
+    #[test]
+    fn rejects_bad_goal_speed_and_spawns() {
+        let level = LevelDef::endless(20, 20);
+        assert!(LevelDef { goal: 0, ..level.clone() }.validate().is_err());
+        assert!(LevelDef { speed: Some(0.0), ..level.clone() }.validate().is_err());
+        assert!(LevelDef { speed: Some(-0.1), ..level.clone() }.validate().is_err());
+        assert!(LevelDef { speed: Some(f64::NAN), ..level.clone() }.validate().is_err());
+        assert_eq!(LevelDef { speed: Some(0.1), ..level.clone() }.validate(), Ok(()));
+        // 蛇身向右延伸3格，蛇头也要在游戏区内
+        assert!(LevelDef { player_spawn: (17, 5), ..level.clone() }.validate().is_err());
+        assert!(LevelDef { player_spawn: (0, 5), ..level.clone() }.validate().is_err());
+        assert!(LevelDef { ai_spawns: vec![(19, 5)], ..level.clone() }.validate().is_err());
+        assert!(LevelDef { ai_spawns: vec![(17, 5)], ..level.clone() }.validate().is_err());
+        let spawn = AiSpawn { at: Some((5, 30)), ..AiSpawn::default() };
+        assert!(LevelDef { ai_schedule: vec![spawn], ..level.clone() }.validate().is_err());
+        let spawn = AiSpawn { speed: Some(0.0), ..AiSpawn::default() };
+        assert!(LevelDef { ai_schedule: vec![spawn], ..level }.validate().is_err());
+    }
+
+    #[test]
+    fn shipped_campaign_loads_and_repeats_its_last_level() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/levels/campaign.toml");
+        let campaign = Campaign::load(&path).unwrap();
+        assert_eq!(campaign.validate(), Ok(()));
+        let last = campaign.levels.last().unwrap();
+        assert_eq!(campaign.level(0), &campaign.levels[0]);
+        assert_eq!(campaign.level(campaign.levels.len() as u32 + 5), last);
+    }
+
+    #[test]
+    fn empty_campaign_is_rejected() {
+        let campaign = Campaign {
+            levels: Vec::new(),
+            ..Campaign::endless(20, 20)
+        };
+        assert!(campaign.validate().is_err());
+    }
+
+    #[test]
+    fn map_must_fit_and_keep_the_board_connected() {
+        // 出生点是 (2, 2)，蛇身占 (2..=4, 2)
+        assert_eq!(with_map(&["", "", "", "", "    ###"]).validate(), Ok(()));
+        assert!(with_map(&["", "", "   #"]).validate().is_err());
+        // AI蛇出生点是 (5, 5)，蛇身占 (5..=7, 5)
+        assert!(with_map(&["", "", "", "", "", "       #"]).validate().is_err());
+        assert!(with_map(&["###########"]).validate().is_err());
+        // 一整行墙把游戏区切成上下两半
+        assert!(with_map(&["", "", "", "", "", "##########"]).validate().is_err());
+        // 被围住的一格也算隔开
+        assert!(with_map(&["", "", "", "", "", "       #", "      # #", "       #"]).validate().is_err());
+    }
+}
@@ -1 +1,14 @@
+pub mod bot;
+pub mod env;
+pub mod events;
 pub mod game;
+pub mod grid;
+pub mod highscore;
+pub mod killcam;
+pub mod level;
+pub mod net;
+pub mod replay;
+pub mod save;
+pub mod settings;
+pub mod sim;
+pub mod speed;
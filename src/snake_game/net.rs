@@ -0,0 +1,251 @@
+use crate::snake_game::game::Game;
+use crate::snake_snake::snake::{AISnake, Direction, Snake};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 联机协议版本，握手时客户端与服务器必须一致
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// 服务器默认监听地址
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+
+/// 连接建立后必须在这段时间内完成握手
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 超过这段时间没有收到对方任何消息就断开
+pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 客户端没有输入时发送心跳的间隔
+pub const PING_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 客户端发给服务器的消息，每条一行 JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// 握手，连接后的第一条消息
+    Hello { version: u32, name: String },
+    /// 改变自己的蛇的方向
+    Turn { dir: Direction },
+    /// 心跳
+    Ping,
+    /// 主动离开
+    Bye,
+}
+
+/// 服务器发给客户端的消息，每条一行 JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// 握手成功，`player` 是分配到的玩家位置
+    Welcome { player: usize, width: i32, height: i32 },
+    /// 握手失败（版本不一致、服务器已满等），随后断开
+    Rejected { reason: String },
+    /// 每个模拟步的局面
+    Snapshot(Snapshot),
+}
+
+/// 一个模拟步结束后的局面
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// 服务器的模拟步
+    pub tick: u64,
+    /// 在场的玩家
+    pub players: Vec<PlayerState>,
+    /// AI蛇
+    pub ai_snakes: Vec<AISnake>,
+    /// 果子坐标
+    pub food: Option<(i32, i32)>,
+    /// AI蛇留下的残骸果子
+    pub leftover_food: Vec<(i32, i32)>,
+    /// 即将出现的AI蛇的出生点
+    pub telegraphs: Vec<(i32, i32)>,
+    /// 障碍物只在变化时和新玩家的第一帧发送，其余时候为 None，沿用上一次的
+    pub obstacles: Option<Vec<(i32, i32)>>,
+}
+
+/// 一位玩家的状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerState {
+    /// 玩家位置
+    pub player: usize,
+    /// 握手时报的名字
+    pub name: String,
+    /// 当前分数
+    pub score: u32,
+    /// 玩家的蛇
+    pub snake: Snake,
+}
+
+impl Snapshot {
+    /// 记录联机游戏当前的局面，`names[i]` 是第 i 个玩家位置的名字
+    pub fn capture(game: &Game, names: &[String], with_obstacles: bool) -> Snapshot {
+        let mut players = Vec::new();
+        if game.is_player_active(0) {
+            players.push(PlayerState {
+                player: 0,
+                name: names.first().cloned().unwrap_or_default(),
+                score: game.get_score(),
+                snake: game.get_snake().clone(),
+            });
+        }
+        for (i, p) in game.get_players().iter().enumerate() {
+            if p.is_active() {
+                players.push(PlayerState {
+                    player: i + 1,
+                    name: names.get(i + 1).cloned().unwrap_or_default(),
+                    score: p.score(),
+                    snake: p.snake().clone(),
+                });
+            }
+        }
+        Snapshot {
+            tick: game.get_tick(),
+            players,
+            ai_snakes: game.ai_snakes.clone(),
+            food: game.get_food(),
+            leftover_food: game.get_leftover_food().clone(),
+            telegraphs: game.get_ai_telegraphs().iter().map(|t| (t.x, t.y)).collect(),
+            obstacles: with_obstacles.then(|| game.get_obstacles().clone()),
+        }
+    }
+}
+
+/// 写出一条消息（一行 JSON）
+pub fn write_message<W: Write, M: Serialize>(w: &mut W, msg: &M) -> io::Result<()> {
+    let mut line = serde_json::to_vec(msg)?;
+    line.push(b'\n');
+    w.write_all(&line)?;
+    w.flush()
+}
+
+/// 读取一条消息，对方关闭连接时返回 None
+pub fn read_message<R: BufRead, M: DeserializeOwned>(r: &mut R) -> io::Result<Option<M>> {
+    let mut line = String::new();
+    if r.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&line)?))
+}
+
+/// 联机客户端：握手后在后台线程接收局面，发送方向和心跳
+pub struct Client {
+    stream: TcpStream,
+    messages: Receiver<ServerMessage>,
+    player: usize,
+    size: (i32, i32),
+    /// 最近收到的障碍物，服务器不发送时沿用
+    obstacles: Vec<(i32, i32)>,
+    last_sent: Instant,
+    connected: bool,
+}
+
+impl Client {
+    /// 连接服务器并握手
+    pub fn connect(addr: &str, name: &str) -> io::Result<Client> {
+        let addr = addr
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无效的服务器地址"))?;
+        let stream = TcpStream::connect_timeout(&addr, HANDSHAKE_TIMEOUT)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+        let mut writer = stream.try_clone()?;
+        write_message(&mut writer, &ClientMessage::Hello { version: PROTOCOL_VERSION, name: name.to_string() })?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let (player, size) = match read_message(&mut reader)? {
+            Some(ServerMessage::Welcome { player, width, height }) => (player, (width, height)),
+            Some(ServerMessage::Rejected { reason }) => {
+                return Err(io::Error::new(io::ErrorKind::ConnectionRefused, reason))
+            }
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "握手失败")),
+        };
+        stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+        let (tx, messages) = mpsc::channel();
+        thread::spawn(move || {
+            while let Ok(Some(msg)) = read_message::<_, ServerMessage>(&mut reader) {
+                if tx.send(msg).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Client {
+            stream,
+            messages,
+            player,
+            size,
+            obstacles: Vec::new(),
+            last_sent: Instant::now(),
+            connected: true,
+        })
+    }
+
+    /// 服务器分配的玩家位置
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    /// 游戏区大小
+    pub fn size(&self) -> (i32, i32) {
+        self.size
+    }
+
+    /// 是否仍然连着服务器
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// 发送方向
+    pub fn turn(&mut self, dir: Direction) {
+        self.send(&ClientMessage::Turn { dir });
+    }
+
+    /// 距离上次发送超过 `PING_INTERVAL` 时发送心跳
+    pub fn keep_alive(&mut self) {
+        if self.last_sent.elapsed() >= PING_INTERVAL {
+            self.send(&ClientMessage::Ping);
+        }
+    }
+
+    /// 取走已经收到的消息，返回最新的局面（障碍物已补全）
+    pub fn poll(&mut self) -> Option<Snapshot> {
+        let mut latest = None;
+        loop {
+            match self.messages.try_recv() {
+                Ok(ServerMessage::Snapshot(mut snapshot)) => {
+                    if let Some(obstacles) = snapshot.obstacles.take() {
+                        self.obstacles = obstacles;
+                    }
+                    snapshot.obstacles = Some(self.obstacles.clone());
+                    latest = Some(snapshot);
+                }
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.connected = false;
+                    break;
+                }
+            }
+        }
+        latest
+    }
+
+    fn send(&mut self, msg: &ClientMessage) {
+        if write_message(&mut self.stream, msg).is_err() {
+            self.connected = false;
+        }
+        self.last_sent = Instant::now();
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        let _ = write_message(&mut self.stream, &ClientMessage::Bye);
+        let _ = self.stream.shutdown(std::net::Shutdown::Both);
+    }
+}
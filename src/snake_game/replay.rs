@@ -0,0 +1,432 @@
+use crate::snake_game::game::{Game, GameEvent, Input, VersusMode, MAX_CATCH_UP_TICKS, TICK};
+use crate::snake_game::level::Campaign;
+use crate::snake_snake::snake::Direction;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// 回放文件头
+const MAGIC: &[u8; 4] = b"SNKR";
+/// 回放文件格式版本：1 只记录游戏区大小（无尽模式），2 记录完整的战役，3 AI蛇改为按策略寻路，
+/// 4 AI蛇会吃果子、变长和死亡，游戏结束后不再移动，5 AI蛇按出生计划预警后出现，6 加入双人对战规则，
+/// 7 转向改为排队
+///
+/// 模拟规则改变时也要递增：旧规则录制的回放要么带上兼容标记按旧规则重放，要么在读取时拒绝。
+const VERSION: u8 = 7;
+/// 还能重现的最早版本，更早的回放录制时AI蛇都在开局一起出现，没有出生预警
+const MIN_VERSION: u8 = 5;
+/// 开始记录双人对战规则的版本
+const VERSUS_VERSION: u8 = 6;
+/// 转向还没有排队的最后一个版本
+const IMMEDIATE_TURNS_VERSION: u8 = 6;
+/// 快进倍率，按顺序循环
+const PLAYBACK_SPEEDS: [f64; 4] = [1.0, 2.0, 4.0, 8.0];
+
+/// 一局的回放：随机种子、战役，以及按模拟步标记的全部输入
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub campaign: Campaign,
+    /// 双人对战的胜负规则，单人游戏为 None
+    #[serde(default)]
+    pub versus: Option<VersusMode>,
+    /// (输入生效时的模拟步, 输入)，按模拟步递增
+    pub inputs: Vec<(u64, Input)>,
+    /// 第7版以前录制的回放，转向不排队而是立即移动
+    #[serde(default)]
+    pub immediate_turns: bool,
+}
+
+impl Replay {
+    /// 创建空的回放
+    pub fn new(seed: u64, campaign: Campaign, versus: Option<VersusMode>) -> Replay {
+        Replay {
+            seed,
+            campaign,
+            versus,
+            inputs: Vec::new(),
+            immediate_turns: false,
+        }
+    }
+
+    /// 记录一次输入
+    pub fn record(&mut self, tick: u64, input: Input) {
+        self.inputs.push((tick, input));
+    }
+
+    /// 按回放的种子和战役创建一局新游戏
+    pub fn new_game(&self) -> Game {
+        let mut game = match self.versus {
+            Some(mode) => Game::new_versus(self.campaign.clone(), self.seed, mode),
+            None => Game::with_campaign(self.campaign.clone(), self.seed),
+        };
+        game.set_immediate_turns(self.immediate_turns);
+        game
+    }
+
+    /// 保存到文件，目录不存在时自动创建
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut buf = Vec::new();
+        self.write_to(&mut buf)?;
+        fs::write(path, buf)
+    }
+
+    /// 从文件读取
+    pub fn load(path: &Path) -> io::Result<Replay> {
+        let mut file = fs::File::open(path)?;
+        Replay::read_from(&mut file)
+    }
+
+    /// 写出紧凑的二进制格式：文件头、种子、战役（JSON）、对战规则（JSON），之后每条输入是模拟步差值（变长整数）+ 一个字节的输入
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        // 旧规则的回放仍按转向不排队的最后一版写出，读回来时规则不变
+        let version = if self.immediate_turns { IMMEDIATE_TURNS_VERSION } else { VERSION };
+        w.write_all(&[version])?;
+        w.write_all(&self.seed.to_le_bytes())?;
+        let campaign = serde_json::to_vec(&self.campaign)?;
+        w.write_all(&(campaign.len() as u32).to_le_bytes())?;
+        w.write_all(&campaign)?;
+        let versus = serde_json::to_vec(&self.versus)?;
+        w.write_all(&(versus.len() as u32).to_le_bytes())?;
+        w.write_all(&versus)?;
+        w.write_all(&(self.inputs.len() as u32).to_le_bytes())?;
+        let mut last_tick = 0;
+        for &(tick, input) in &self.inputs {
+            write_varint(w, tick - last_tick)?;
+            w.write_all(&[encode_input(input)])?;
+            last_tick = tick;
+        }
+        Ok(())
+    }
+
+    /// 读取 `write_to` 写出的格式，也兼容没有对战规则和转向不排队的旧版本
+    ///
+    /// 模拟规则已经改变、无法重现的旧版本会被拒绝。
+    pub fn read_from<R: Read>(r: &mut R) -> io::Result<Replay> {
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("不是回放文件"));
+        }
+        let version = read_u8(r)?;
+        if version < MIN_VERSION {
+            return Err(invalid_data(&format!("第{}版回放录制时的AI蛇规则已经改变，无法重现", version)));
+        }
+        if version > VERSION {
+            return Err(invalid_data("不支持的回放版本"));
+        }
+        let mut buf8 = [0u8; 8];
+        let mut buf4 = [0u8; 4];
+        r.read_exact(&mut buf8)?;
+        let seed = u64::from_le_bytes(buf8);
+        let campaign: Campaign = serde_json::from_slice(&read_block(r)?)?;
+        campaign.validate().map_err(|e| invalid_data(&e))?;
+        let versus = if version >= VERSUS_VERSION {
+            serde_json::from_slice(&read_block(r)?)?
+        } else {
+            None
+        };
+        r.read_exact(&mut buf4)?;
+        let count = u32::from_le_bytes(buf4);
+        let mut replay = Replay::new(seed, campaign, versus);
+        replay.immediate_turns = version <= IMMEDIATE_TURNS_VERSION;
+        let mut tick = 0;
+        for _ in 0..count {
+            tick += read_varint(r)?;
+            let input = decode_input(read_u8(r)?).ok_or_else(|| invalid_data("未知的输入"))?;
+            replay.record(tick, input);
+        }
+        Ok(replay)
+    }
+}
+
+/// 回放播放器：把记录的输入在对应的模拟步重新喂给 `Game`，支持暂停、快进和逐帧
+#[derive(Debug)]
+pub struct ReplayPlayer {
+    replay: Replay,
+    /// 下一条待播放的输入
+    cursor: usize,
+    accumulator: f64,
+    paused: bool,
+    /// 暂停时请求单步
+    step_requested: bool,
+    speed_idx: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> ReplayPlayer {
+        ReplayPlayer {
+            replay,
+            cursor: 0,
+            accumulator: 0.0,
+            paused: false,
+            step_requested: false,
+            speed_idx: 0,
+        }
+    }
+
+    /// 按回放的种子和战役创建一局新游戏
+    pub fn new_game(&self) -> Game {
+        self.replay.new_game()
+    }
+
+    /// 按真实时间推进回放，暂停时只执行被请求的单步
+    pub fn advance(&mut self, game: &mut Game, delta_time: f64) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.paused {
+            if self.step_requested {
+                self.step_requested = false;
+                events.extend(self.step_tick(game));
+            }
+            return events;
+        }
+        self.accumulator += delta_time * self.speed();
+        let max_steps = MAX_CATCH_UP_TICKS * PLAYBACK_SPEEDS[self.speed_idx] as u32;
+        let mut steps = 0;
+        while self.accumulator >= TICK {
+            if steps == max_steps {
+                self.accumulator = 0.0;
+                break;
+            }
+            events.extend(self.step_tick(game));
+            self.accumulator -= TICK;
+            steps += 1;
+        }
+        events
+    }
+
+    /// 应用当前模拟步记录的输入，并执行一个模拟步
+    pub fn step_tick(&mut self, game: &mut Game) -> Vec<GameEvent> {
+        let mut inputs = Vec::new();
+        while let Some(&(tick, input)) = self.replay.inputs.get(self.cursor) {
+            if tick > game.get_tick() {
+                break;
+            }
+            inputs.push(input);
+            self.cursor += 1;
+        }
+        game.tick(&inputs)
+    }
+
+    /// 暂停/继续
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// 暂停时前进一个模拟步
+    pub fn request_step(&mut self) {
+        if self.paused {
+            self.step_requested = true;
+        }
+    }
+
+    /// 切换到下一档快进倍率
+    pub fn fast_forward(&mut self) {
+        self.speed_idx = (self.speed_idx + 1) % PLAYBACK_SPEEDS.len();
+    }
+
+    /// 是否暂停
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// 当前播放倍率
+    pub fn speed(&self) -> f64 {
+        PLAYBACK_SPEEDS[self.speed_idx]
+    }
+
+    /// 记录的输入是否已经全部播放
+    pub fn inputs_exhausted(&self) -> bool {
+        self.cursor >= self.replay.inputs.len()
+    }
+}
+
+fn encode_input(input: Input) -> u8 {
+    match input {
+        Input::Turn(Direction::Up) => 0,
+        Input::Turn(Direction::Down) => 1,
+        Input::Turn(Direction::Left) => 2,
+        Input::Turn(Direction::Right) => 3,
+        Input::TogglePause => 4,
+        Input::Restart => 5,
+        Input::NextLevel => 6,
+        Input::Turn2(Direction::Up) => 7,
+        Input::Turn2(Direction::Down) => 8,
+        Input::Turn2(Direction::Left) => 9,
+        Input::Turn2(Direction::Right) => 10,
+        Input::RestartLevel => 11,
+        // 最高位表示联机玩家，中间5位是玩家，最低2位是方向
+        Input::TurnPlayer { player, dir } => 0x80 | (player & 0x1f) << 2 | direction_bits(dir),
+    }
+}
+
+fn direction_bits(dir: Direction) -> u8 {
+    match dir {
+        Direction::Up => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Right => 3,
+    }
+}
+
+fn decode_input(byte: u8) -> Option<Input> {
+    if byte & 0x80 != 0 {
+        let dir = [Direction::Up, Direction::Down, Direction::Left, Direction::Right][(byte & 0x03) as usize];
+        return Some(Input::TurnPlayer { player: (byte >> 2) & 0x1f, dir });
+    }
+    match byte {
+        0 => Some(Input::Turn(Direction::Up)),
+        1 => Some(Input::Turn(Direction::Down)),
+        2 => Some(Input::Turn(Direction::Left)),
+        3 => Some(Input::Turn(Direction::Right)),
+        4 => Some(Input::TogglePause),
+        5 => Some(Input::Restart),
+        6 => Some(Input::NextLevel),
+        7 => Some(Input::Turn2(Direction::Up)),
+        8 => Some(Input::Turn2(Direction::Down)),
+        9 => Some(Input::Turn2(Direction::Left)),
+        10 => Some(Input::Turn2(Direction::Right)),
+        11 => Some(Input::RestartLevel),
+        _ => None,
+    }
+}
+
+fn write_varint<W: Write>(w: &mut W, mut value: u64) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return w.write_all(&[byte]);
+        }
+        w.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = read_u8(r)?;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data("变长整数过长"))
+}
+
+/// 读取长度前缀（u32）加内容的数据块
+fn read_block<R: Read>(r: &mut R) -> io::Result<Vec<u8>> {
+    let mut buf4 = [0u8; 4];
+    r.read_exact(&mut buf4)?;
+    let mut block = vec![0u8; u32::from_le_bytes(buf4) as usize];
+    r.read_exact(&mut block)?;
+    Ok(block)
+}
+
+fn read_u8<R: Read>(r: &mut R) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    r.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    fn all_inputs() -> Vec<Input> {
+        let mut inputs = vec![Input::TogglePause, Input::Restart, Input::NextLevel, Input::RestartLevel];
+        for dir in DIRECTIONS {
+            inputs.push(Input::Turn(dir));
+            inputs.push(Input::Turn2(dir));
+            for player in 0..32 {
+                inputs.push(Input::TurnPlayer { player, dir });
+            }
+        }
+        inputs
+    }
+
+    fn encode(replay: &Replay) -> Vec<u8> {
+        let mut buf = Vec::new();
+        replay.write_to(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn every_input_survives_one_byte() {
+        for input in all_inputs() {
+            assert_eq!(decode_input(encode_input(input)), Some(input));
+        }
+        assert_eq!(decode_input(12), None);
+    }
+
+    #[test]
+    fn replays_round_trip() {
+        let mut replay = Replay::new(99, Campaign::endless(20, 20), Some(VersusMode::FirstTo { food: 3 }));
+        for (i, input) in all_inputs().into_iter().enumerate() {
+            replay.record(i as u64 * 1000, input);
+        }
+        let buf = encode(&replay);
+        assert_eq!(buf[4], VERSION);
+        assert_eq!(Replay::read_from(&mut &buf[..]).unwrap(), replay);
+    }
+
+    #[test]
+    fn replays_from_before_the_turn_queue_keep_their_version() {
+        let mut replay = Replay::new(99, Campaign::endless(20, 20), None);
+        replay.record(3, Input::Turn(Direction::Up));
+        replay.immediate_turns = true;
+        let buf = encode(&replay);
+        assert_eq!(buf[4], IMMEDIATE_TURNS_VERSION);
+        let read = Replay::read_from(&mut &buf[..]).unwrap();
+        assert!(read.immediate_turns);
+        assert_eq!(read, replay);
+    }
+
+    #[test]
+    fn playback_reproduces_the_recorded_game() {
+        let mut game = Game::new_with_seed(20, 20, 5);
+        for i in 0..3000u64 {
+            let inputs = match i % 200 {
+                10 => vec![Input::Turn(Direction::Down)],
+                70 => vec![Input::Turn(Direction::Right)],
+                130 => vec![Input::Turn(Direction::Up)],
+                150 => vec![Input::Turn(Direction::Right)],
+                _ => vec![],
+            };
+            game.tick(&inputs);
+            if game.is_game_over() {
+                game.tick(&[Input::Restart]);
+            }
+        }
+        let buf = encode(game.get_replay());
+        let mut player = ReplayPlayer::new(Replay::read_from(&mut &buf[..]).unwrap());
+        let mut replayed = player.new_game();
+        while replayed.get_tick() < game.get_tick() {
+            player.step_tick(&mut replayed);
+        }
+        assert!(player.inputs_exhausted());
+        assert_eq!(serde_json::to_value(&replayed).unwrap(), serde_json::to_value(&game).unwrap());
+    }
+
+    #[test]
+    fn rejects_unreproducible_unknown_and_foreign_files() {
+        let mut buf = encode(&Replay::new(1, Campaign::endless(20, 20), None));
+        for version in [1, MIN_VERSION - 1, VERSION + 1] {
+            buf[4] = version;
+            assert_eq!(Replay::read_from(&mut &buf[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        }
+        buf[0] = b'X';
+        assert_eq!(Replay::read_from(&mut &buf[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
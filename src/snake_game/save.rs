@@ -0,0 +1,170 @@
+use crate::snake_game::game::Game;
+use crate::snake_game::level::Campaign;
+use serde_json::Value;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// 存档格式版本，`Game` 的存档结构变化时递增，并在 `migrate` 中补上旧版本的迁移
+///
+/// * 1：无尽模式，只有游戏区大小和速度曲线
+/// * 2：加入战役（关卡定义）
+pub const SAVE_VERSION: u32 = 2;
+
+/// 把进行中的一局写入存档
+pub fn save_game(game: &Game, path: &Path) -> io::Result<()> {
+    let value = serde_json::json!({
+        "version": SAVE_VERSION,
+        "game": game,
+    });
+    write_atomic(path, serde_json::to_string(&value)?.as_bytes())
+}
+
+/// 读取存档并恢复当时的 `Game`
+///
+/// 比当前版本新的存档会被拒绝，旧版本的存档先迁移到当前版本再读取。
+pub fn load_game(path: &Path) -> io::Result<Game> {
+    let text = fs::read_to_string(path)?;
+    let mut value: Value = serde_json::from_str(&text)?;
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| invalid_data("存档缺少版本号".to_string()))? as u32;
+    if version > SAVE_VERSION {
+        return Err(invalid_data(format!(
+            "存档版本 {} 比当前游戏支持的版本 {} 新",
+            version, SAVE_VERSION
+        )));
+    }
+    let game = migrate(value["game"].take(), version)?;
+    let mut game: Game = serde_json::from_value(game)?;
+    game.get_campaign().validate().map_err(invalid_data)?;
+    game.rebuild_grid();
+    Ok(game)
+}
+
+/// 删除存档，存档不存在时不算错误
+pub fn delete_save(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// 把 `version` 版本的存档内容逐级迁移到 `SAVE_VERSION`
+fn migrate(mut game: Value, mut version: u32) -> io::Result<Value> {
+    while version < SAVE_VERSION {
+        game = match version {
+            1 => migrate_v1(game)?,
+            _ => return Err(invalid_data(format!("不支持的存档版本 {}", version))),
+        };
+        version += 1;
+    }
+    Ok(game)
+}
+
+/// 1 -> 2：无尽模式的游戏区大小和速度曲线并入战役，回放也改为记录战役
+fn migrate_v1(mut game: Value) -> io::Result<Value> {
+    let size = |key: &str| {
+        game.get(key)
+            .and_then(Value::as_i64)
+            .map(|v| v as i32)
+            .ok_or_else(|| invalid_data(format!("存档缺少 {}", key)))
+    };
+    let mut campaign = Campaign::endless(size("width")?, size("height")?);
+    // 第1版的速度曲线是 {"Capped": {...}} 形式
+    if let Some(Value::Object(curve)) = game.get("speed_curve") {
+        if let Some((kind, fields)) = curve.iter().next() {
+            let mut fields = fields.clone();
+            fields["type"] = Value::String(kind.to_lowercase());
+            campaign.speed_curve = serde_json::from_value(fields)?;
+        }
+    }
+    let campaign = serde_json::to_value(&campaign)?;
+    let obj = game
+        .as_object_mut()
+        .ok_or_else(|| invalid_data("存档格式错误".to_string()))?;
+    obj.remove("speed_curve");
+    obj.insert("campaign".to_string(), campaign.clone());
+    if let Some(Value::Object(replay)) = obj.get_mut("replay") {
+        replay.remove("width");
+        replay.remove("height");
+        replay.insert("campaign".to_string(), campaign);
+    }
+    Ok(game)
+}
+
+/// 先写临时文件再重命名，写到一半崩溃也不会损坏原文件；目录不存在时自动创建
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake_game::speed::SpeedCurve;
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("snake-save-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn migrate_v1_moves_size_and_speed_curve_into_the_campaign() {
+        let text = fs::read_to_string(fixture("save_v1.json")).unwrap();
+        let mut value: Value = serde_json::from_str(&text).unwrap();
+        let game = migrate_v1(value["game"].take()).unwrap();
+        assert!(game.get("width").is_some() && game.get("speed_curve").is_none());
+        let campaign: Campaign = serde_json::from_value(game["campaign"].clone()).unwrap();
+        assert_eq!(campaign.levels[0].width, 20);
+        assert_eq!(campaign.levels[0].height, 20);
+        assert_eq!(campaign.speed_curve, SpeedCurve::Capped { base: 0.18, ratio: 0.5, min: 0.04 });
+        assert_eq!(game["replay"]["campaign"], game["campaign"]);
+        assert!(game["replay"].get("width").is_none());
+    }
+
+    #[test]
+    fn v1_save_loads_and_keeps_playing() {
+        let mut game = load_game(&fixture("save_v1.json")).unwrap();
+        assert_eq!(game.get_size(), (20, 20));
+        assert_eq!(game.get_tick(), 30);
+        assert!(!game.is_game_over());
+        for _ in 0..60 {
+            game.tick(&[]);
+        }
+        assert_eq!(game.get_tick(), 90);
+    }
+
+    #[test]
+    fn round_trip_and_newer_versions_are_rejected() {
+        let path = temp_path("round_trip.json");
+        let mut game = Game::new_with_seed(20, 20, 9);
+        for _ in 0..100 {
+            game.tick(&[]);
+        }
+        save_game(&game, &path).unwrap();
+        let loaded = load_game(&path).unwrap();
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&game).unwrap());
+
+        let newer = serde_json::json!({ "version": SAVE_VERSION + 1, "game": game });
+        write_atomic(&path, newer.to_string().as_bytes()).unwrap();
+        assert_eq!(load_game(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        delete_save(&path).unwrap();
+        // 不存在的存档删除也不算错误
+        delete_save(&path).unwrap();
+    }
+}
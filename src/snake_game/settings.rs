@@ -0,0 +1,232 @@
+use crate::snake_game::level::{AiSpawn, Campaign};
+use crate::snake_game::save::write_atomic;
+use crate::snake_game::speed::MIN_MOVING_PERIOD;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 无尽模式游戏区边长（含边框）的范围
+pub const GRID_RANGE: (i32, i32) = (10, 60);
+/// 速度倍率的范围
+pub const SPEED_RANGE: (f64, f64) = (0.5, 2.0);
+/// 每关AI蛇数量的上限
+pub const MAX_AI_SNAKES: usize = 6;
+/// 音量的上限
+pub const MAX_VOLUME: u32 = 100;
+/// 窗口的最小尺寸，界面按这个尺寸排版
+pub const MIN_WINDOW: (u32, u32) = (700, 800);
+
+/// 难度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    /// 慢一些，每关少一条AI蛇
+    Easy,
+    #[default]
+    Normal,
+    /// 快一些，每关多一条AI蛇
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "简单",
+            Difficulty::Normal => "普通",
+            Difficulty::Hard => "困难",
+        }
+    }
+
+    /// 在速度倍率之上再乘的倍率
+    fn speed_factor(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+        }
+    }
+
+    /// 每关AI蛇数量的增减
+    fn ai_delta(self) -> i32 {
+        match self {
+            Difficulty::Easy => -1,
+            Difficulty::Normal => 0,
+            Difficulty::Hard => 1,
+        }
+    }
+}
+
+/// 玩家可以调整的全部设置，保存在配置文件里
+///
+/// 难度、速度和AI蛇数量在开始新的一局时通过 `apply` 写进战役，
+/// 所以存档和回放里记录的是调整后的战役，读回来时不受当前设置影响。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub difficulty: Difficulty,
+    /// 用无尽模式代替默认战役
+    pub endless: bool,
+    /// 无尽模式游戏区的宽和高（含边框）
+    pub grid_width: i32,
+    pub grid_height: i32,
+    /// 速度倍率，1 为原速，越大越快
+    pub speed: f64,
+    /// 每关AI蛇数量，None 为按关卡的定义
+    pub ai_snakes: Option<usize>,
+    /// 画面抽搐、闪光、符号闪现等特效的强度，0 到 1
+    pub effects: f64,
+    /// 音量，0 到 `MAX_VOLUME`；游戏还没有音效，先保存下来留给以后的音频输出
+    pub volume: u32,
+    /// 窗口大小
+    pub window_width: u32,
+    pub window_height: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            difficulty: Difficulty::Normal,
+            endless: false,
+            grid_width: 30,
+            grid_height: 30,
+            speed: 1.0,
+            ai_snakes: None,
+            effects: 1.0,
+            volume: 80,
+            window_width: MIN_WINDOW.0,
+            window_height: MIN_WINDOW.1,
+        }
+    }
+}
+
+impl Settings {
+    /// 读取设置，文件不存在时使用默认设置；没有写的项使用默认值
+    pub fn load(path: &Path) -> io::Result<Settings> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Settings::default()),
+            Err(e) => return Err(e),
+        };
+        let settings: Settings =
+            toml::from_str(&text).map_err(|e| invalid_data(format!("{}: {}", path.display(), e)))?;
+        settings
+            .validate()
+            .map_err(|e| invalid_data(format!("{}: {}", path.display(), e)))?;
+        Ok(settings)
+    }
+
+    /// 原子地写入设置
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let text = toml::to_string_pretty(self).map_err(|e| invalid_data(e.to_string()))?;
+        write_atomic(path, text.as_bytes())
+    }
+
+    /// 检查每一项是否在允许的范围内
+    pub fn validate(&self) -> Result<(), String> {
+        let (min, max) = GRID_RANGE;
+        if !(min..=max).contains(&self.grid_width) || !(min..=max).contains(&self.grid_height) {
+            return Err(format!("游戏区大小必须在 {} 到 {} 之间", min, max));
+        }
+        if !(SPEED_RANGE.0..=SPEED_RANGE.1).contains(&self.speed) {
+            return Err(format!("速度倍率必须在 {} 到 {} 之间", SPEED_RANGE.0, SPEED_RANGE.1));
+        }
+        if self.ai_snakes.is_some_and(|n| n > MAX_AI_SNAKES) {
+            return Err(format!("AI蛇数量不能超过 {}", MAX_AI_SNAKES));
+        }
+        if !(0.0..=1.0).contains(&self.effects) {
+            return Err("特效强度必须在 0 到 1 之间".to_string());
+        }
+        if self.volume > MAX_VOLUME {
+            return Err(format!("音量必须在 0 到 {} 之间", MAX_VOLUME));
+        }
+        if self.window_width < MIN_WINDOW.0 || self.window_height < MIN_WINDOW.1 {
+            return Err(format!("窗口不能小于 {}x{}", MIN_WINDOW.0, MIN_WINDOW.1));
+        }
+        Ok(())
+    }
+
+    /// 按设置选择战役：无尽模式时用设置的游戏区大小，否则用 `campaign`
+    pub fn campaign(&self, campaign: Option<&Campaign>) -> Campaign {
+        match campaign {
+            Some(campaign) if !self.endless => self.apply(campaign),
+            _ => self.apply(&Campaign::endless(self.grid_width, self.grid_height)),
+        }
+    }
+
+    /// 把难度、速度和AI蛇数量写进战役的每一关
+    pub fn apply(&self, campaign: &Campaign) -> Campaign {
+        let mut campaign = campaign.clone();
+        let factor = self.speed * self.difficulty.speed_factor();
+        campaign.speed_curve = campaign.speed_curve.scaled(factor);
+        for level in &mut campaign.levels {
+            level.speed = level.speed.map(|s| (s / factor).max(MIN_MOVING_PERIOD));
+            let base = self.ai_snakes.unwrap_or_else(|| level.schedule().len()) as i32;
+            let count = (base + self.difficulty.ai_delta()).max(0) as usize;
+            // 出生计划不够时按最后一条补齐，出生点随机
+            if let Some(last) = level.ai_schedule.last().cloned() {
+                level.ai_schedule.resize(count, AiSpawn { at: None, ..last });
+            }
+            for spawn in &mut level.ai_schedule {
+                spawn.speed = spawn.speed.map(|s| (s / factor).max(MIN_MOVING_PERIOD));
+            }
+            // 出生计划减到空时改由 `ai_snakes` 决定
+            if level.ai_schedule.is_empty() {
+                level.ai_snakes = count;
+            }
+        }
+        campaign
+    }
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_valid_and_survive_toml() {
+        let settings = Settings::default();
+        assert_eq!(settings.validate(), Ok(()));
+        let text = toml::to_string_pretty(&settings).unwrap();
+        assert_eq!(toml::from_str::<Settings>(&text).unwrap(), settings);
+        // 没有写的项使用默认值
+        let partial: Settings = toml::from_str("speed = 1.5").unwrap();
+        assert_eq!(partial, Settings { speed: 1.5, ..Settings::default() });
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        let ok = Settings::default();
+        assert!(Settings { grid_width: GRID_RANGE.0 - 1, ..ok.clone() }.validate().is_err());
+        assert!(Settings { speed: SPEED_RANGE.1 + 0.1, ..ok.clone() }.validate().is_err());
+        assert!(Settings { ai_snakes: Some(MAX_AI_SNAKES + 1), ..ok.clone() }.validate().is_err());
+        assert!(Settings { effects: 1.5, ..ok.clone() }.validate().is_err());
+        assert!(Settings { volume: MAX_VOLUME + 1, ..ok.clone() }.validate().is_err());
+        assert_eq!(Settings { volume: MAX_VOLUME, ..ok.clone() }.validate(), Ok(()));
+        assert!(Settings { window_width: MIN_WINDOW.0 - 1, ..ok }.validate().is_err());
+    }
+
+    #[test]
+    fn apply_writes_speed_and_ai_count_into_the_campaign() {
+        let settings = Settings {
+            speed: 2.0,
+            ai_snakes: Some(2),
+            difficulty: Difficulty::Normal,
+            ..Settings::default()
+        };
+        let base = Campaign::endless(20, 20);
+        let campaign = settings.apply(&base);
+        let factor = Difficulty::Normal.speed_factor() * 2.0;
+        let expected = base.speed_curve.moving_period(1) / factor;
+        assert!((campaign.speed_curve.moving_period(1) - expected).abs() < 1e-9);
+        let ai = (2 + Difficulty::Normal.ai_delta()).max(0) as usize;
+        assert_eq!(campaign.levels[0].schedule().len(), ai);
+        assert_eq!(campaign.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::snake_game::bot::BotDriver;
+use crate::snake_game::events::EventStats;
+use crate::snake_game::game::{DeathCause, Game, GameEvent, Input};
+use crate::snake_snake::ai::{AiStrategy, AiView};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+/// 控制玩家蛇的一方
+pub enum Pilot {
+    /// 借用AI蛇的寻路策略
+    Strategy(AiStrategy),
+    /// 外部机器人
+    Bot(BotDriver),
+}
+
+/// 一局怎么结束的
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunEnd {
+    /// 玩家死亡
+    Died,
+    /// 梦境已满，玩家获胜
+    BoardFull,
+    /// 达到步数上限时还活着
+    MaxTicks,
+}
+
+/// 一局模拟的结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunResult {
+    pub seed: u64,
+    pub end: RunEnd,
+    /// 死因，没有死亡时为 None
+    pub cause: Option<DeathCause>,
+    pub score: u32,
+    /// 到达的关卡
+    pub level: u32,
+    /// 存活的模拟步数（60 步一秒，不含过关等待）
+    pub ticks: u64,
+    /// 结束时蛇的长度
+    pub length: usize,
+    /// 吃到的果子（包括AI蛇的残骸）
+    pub food: u32,
+}
+
+impl RunResult {
+    /// 记录一局结束时的结果
+    pub fn from_game(game: &Game, stats: &EventStats) -> RunResult {
+        let end = if game.is_board_full() {
+            RunEnd::BoardFull
+        } else if game.is_game_over() {
+            RunEnd::Died
+        } else {
+            RunEnd::MaxTicks
+        };
+        RunResult {
+            seed: game.get_seed(),
+            end,
+            cause: if end == RunEnd::Died { game.get_death_cause() } else { None },
+            score: game.get_score(),
+            level: game.get_level(),
+            ticks: game.get_play_ticks(),
+            length: game.get_snake().body().count(),
+            food: stats.food_eaten,
+        }
+    }
+}
+
+impl Pilot {
+    /// 需要时决定方向，然后执行一个模拟步
+    pub fn step_tick(&mut self, game: &mut Game, rng: &mut ChaCha8Rng) -> Vec<GameEvent> {
+        match self {
+            Pilot::Bot(driver) => driver.step_tick(game, &[]),
+            Pilot::Strategy(strategy) => {
+                let mut inputs = Vec::new();
+                if game.is_player_move_due() {
+                    let snake = game.get_snake();
+                    let head = snake.head_position();
+                    // 猎手策略去堵最近的AI蛇
+                    let prey = game
+                        .ai_snakes
+                        .iter()
+                        .min_by_key(|ai| {
+                            let (x, y) = ai.head_position();
+                            (x - head.0).abs() + (y - head.1).abs()
+                        })
+                        .map(|ai| (ai.head_position(), ai.direction))
+                        .unwrap_or((head, snake.head_direction()));
+                    let view = AiView {
+                        grid: game.get_grid(),
+                        head,
+                        direction: snake.head_direction(),
+                        length: snake.body().count(),
+                        player_head: prey.0,
+                        player_direction: prey.1,
+                        food: game.get_food(),
+                    };
+                    let dir = strategy.controller().choose_direction(&view, rng);
+                    if dir != snake.head_direction() {
+                        inputs.push(Input::Turn(dir));
+                    }
+                }
+                game.tick(&inputs)
+            }
+        }
+    }
+}
+
+/// 无人值守地玩完一局：过关后直接进入下一关，死亡、梦境已满或执行了 `max_ticks` 个模拟步后停止
+///
+/// 内置策略的随机数与游戏的随机数分开，由种子决定，同样的参数总是得到同样的结果。
+pub fn play(game: &mut Game, pilot: &mut Pilot, max_ticks: u64) -> RunResult {
+    let mut rng = ChaCha8Rng::seed_from_u64(game.get_seed());
+    let mut stats = EventStats::default();
+    while !game.is_game_over() && game.get_tick() < max_ticks {
+        let mut cleared = false;
+        for event in pilot.step_tick(game, &mut rng) {
+            stats.record(&event);
+            cleared |= matches!(event, GameEvent::LevelCleared { .. });
+        }
+        if cleared {
+            game.handle_input(Input::NextLevel);
+        }
+    }
+    RunResult::from_game(game, &stats)
+}
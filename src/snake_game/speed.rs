@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+/// 移动周期的下限，再快就比一个模拟步还短了
+pub const MIN_MOVING_PERIOD: f64 = 0.04;
+
+/// 玩家速度曲线：第 N 关的移动周期（秒，越小越快）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SpeedCurve {
+    /// 每关减少固定的周期
+    Linear { base: f64, step: f64 },
+    /// 每关乘以固定比例
+    Geometric { base: f64, ratio: f64 },
+    /// 每关乘以固定比例，但不会快过 `min`
+    Capped { base: f64, ratio: f64, min: f64 },
+}
+
+impl Default for SpeedCurve {
+    /// 默认每关速度翻倍，最快 0.04 秒移动一次
+    fn default() -> SpeedCurve {
+        SpeedCurve::Capped {
+            base: 0.18,
+            ratio: 0.5,
+            min: MIN_MOVING_PERIOD,
+        }
+    }
+}
+
+impl SpeedCurve {
+    /// 第 `level` 关（从1开始）的移动周期
+    pub fn moving_period(&self, level: u32) -> f64 {
+        let n = level.saturating_sub(1) as f64;
+        let period = match *self {
+            SpeedCurve::Linear { base, step } => base - step * n,
+            SpeedCurve::Geometric { base, ratio } => base * ratio.powf(n),
+            SpeedCurve::Capped { base, ratio, min } => (base * ratio.powf(n)).max(min),
+        };
+        period.max(MIN_MOVING_PERIOD)
+    }
+
+    /// 整体加快 `factor` 倍（小于 1 时变慢）的曲线
+    pub fn scaled(&self, factor: f64) -> SpeedCurve {
+        match *self {
+            SpeedCurve::Linear { base, step } => SpeedCurve::Linear {
+                base: base / factor,
+                step: step / factor,
+            },
+            SpeedCurve::Geometric { base, ratio } => SpeedCurve::Geometric {
+                base: base / factor,
+                ratio,
+            },
+            SpeedCurve::Capped { base, ratio, min } => SpeedCurve::Capped {
+                base: base / factor,
+                ratio,
+                min: min / factor,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn default_curve_halves_until_the_cap() {
+        let curve = SpeedCurve::default();
+        assert!(close(curve.moving_period(1), 0.18));
+        assert!(close(curve.moving_period(2), 0.09));
+        assert!(close(curve.moving_period(3), 0.045));
+        assert!(close(curve.moving_period(4), MIN_MOVING_PERIOD));
+        assert!(close(curve.moving_period(50), MIN_MOVING_PERIOD));
+        // 第0关按第1关算
+        assert!(close(curve.moving_period(0), 0.18));
+    }
+
+    #[test]
+    fn linear_and_geometric_never_go_below_the_floor() {
+        let linear = SpeedCurve::Linear { base: 0.2, step: 0.05 };
+        assert!(close(linear.moving_period(2), 0.15));
+        assert!(close(linear.moving_period(10), MIN_MOVING_PERIOD));
+        let geometric = SpeedCurve::Geometric { base: 0.2, ratio: 0.9 };
+        assert!(close(geometric.moving_period(3), 0.2 * 0.81));
+        assert!(close(geometric.moving_period(100), MIN_MOVING_PERIOD));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::snake_game::grid::Grid;
+use crate::snake_snake::snake::Direction;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+/// 猎手瞄准玩家蛇头前方最多多少格
+const HUNT_LOOKAHEAD: i32 = 3;
+
+/// AI蛇做决定时能看到的局面
+pub struct AiView<'a> {
+    /// 占用网格
+    pub grid: &'a Grid,
+    /// 这条AI蛇的蛇头
+    pub head: (i32, i32),
+    /// 这条AI蛇当前的方向
+    pub direction: Direction,
+    /// 这条AI蛇的长度
+    pub length: usize,
+    /// 玩家蛇头
+    pub player_head: (i32, i32),
+    /// 玩家蛇的方向
+    pub player_direction: Direction,
+    /// 果子位置，不存在时为 None
+    pub food: Option<(i32, i32)>,
+}
+
+/// AI蛇的控制器：每次移动前根据局面选择方向
+pub trait AiController {
+    fn choose_direction(&self, view: &AiView, rng: &mut ChaCha8Rng) -> Direction;
+}
+
+/// AI蛇的行为策略，可在关卡文件中指定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AiStrategy {
+    /// 随机游走，但不会掉头或撞墙
+    #[default]
+    Wander,
+    /// 沿最短路径去抢果子
+    FoodSeeker,
+    /// 抢到玩家蛇头前面截杀
+    Hunter,
+    /// 往空间最大的方向走，避开死路
+    Survivor,
+}
+
+impl AiStrategy {
+    /// 关卡没有指定时，第 `level` 关第 `index` 条AI蛇的策略，关卡越高越有敌意
+    pub fn for_level(level: u32, index: usize) -> AiStrategy {
+        match level {
+            1 => AiStrategy::Wander,
+            2 => AiStrategy::FoodSeeker,
+            3 => [AiStrategy::FoodSeeker, AiStrategy::Hunter][index % 2],
+            _ => [AiStrategy::Hunter, AiStrategy::Survivor, AiStrategy::FoodSeeker][index % 3],
+        }
+    }
+
+    /// 对应的控制器
+    pub fn controller(self) -> &'static dyn AiController {
+        match self {
+            AiStrategy::Wander => &Wander,
+            AiStrategy::FoodSeeker => &FoodSeeker,
+            AiStrategy::Hunter => &Hunter,
+            AiStrategy::Survivor => &Survivor,
+        }
+    }
+}
+
+/// 随机游走
+pub struct Wander;
+
+impl AiController for Wander {
+    fn choose_direction(&self, view: &AiView, rng: &mut ChaCha8Rng) -> Direction {
+        let forward_safe = is_safe(view.grid, step(view.head, view.direction));
+        if forward_safe && !rng.gen_bool(0.1) {
+            return view.direction;
+        }
+        safe_directions(view).choose(rng).copied().unwrap_or(view.direction)
+    }
+}
+
+/// 抢果子：广度优先搜索最短路径，走进死路前改用求生策略
+pub struct FoodSeeker;
+
+impl AiController for FoodSeeker {
+    fn choose_direction(&self, view: &AiView, rng: &mut ChaCha8Rng) -> Direction {
+        view.food
+            .and_then(|food| first_step_towards(view, food))
+            .filter(|&dir| room_after(view, dir) >= view.length)
+            .unwrap_or_else(|| Survivor.choose_direction(view, rng))
+    }
+}
+
+/// 猎手：去玩家蛇头前方的格子堵截
+pub struct Hunter;
+
+impl AiController for Hunter {
+    fn choose_direction(&self, view: &AiView, rng: &mut ChaCha8Rng) -> Direction {
+        // 由远到近找一个能到达的拦截点
+        (1..=HUNT_LOOKAHEAD)
+            .rev()
+            .map(|k| step_n(view.player_head, view.player_direction, k))
+            .filter(|&cell| !view.grid.is_blocked(cell.0, cell.1))
+            .find_map(|cell| first_step_towards(view, cell))
+            .unwrap_or_else(|| Survivor.choose_direction(view, rng))
+    }
+}
+
+/// 求生：比较每个方向之后能活动的空间，走向最大的一边，相同时优先保持方向
+pub struct Survivor;
+
+impl AiController for Survivor {
+    fn choose_direction(&self, view: &AiView, rng: &mut ChaCha8Rng) -> Direction {
+        let mut best: Vec<Direction> = Vec::new();
+        let mut best_room = 0;
+        for dir in safe_directions(view) {
+            let room = room_after(view, dir);
+            if room > best_room {
+                best_room = room;
+                best.clear();
+            }
+            if room == best_room {
+                best.push(dir);
+            }
+        }
+        if best.contains(&view.direction) {
+            return view.direction;
+        }
+        best.choose(rng).copied().unwrap_or(view.direction)
+    }
+}
+
+/// AI蛇能否走进该格：不是边框、障碍物或任何蛇身
+fn is_safe(grid: &Grid, (x, y): (i32, i32)) -> bool {
+    !grid.is_blocked(x, y) && !grid.has_ai(x, y) && !grid.has_player(x, y)
+}
+
+fn step(pos: (i32, i32), dir: Direction) -> (i32, i32) {
+    step_n(pos, dir, 1)
+}
+
+fn step_n((x, y): (i32, i32), dir: Direction, n: i32) -> (i32, i32) {
+    match dir {
+        Direction::Up => (x, y - n),
+        Direction::Down => (x, y + n),
+        Direction::Left => (x - n, y),
+        Direction::Right => (x + n, y),
+    }
+}
+
+/// 不掉头、下一格安全的方向
+fn safe_directions(view: &AiView) -> Vec<Direction> {
+    DIRECTIONS
+        .into_iter()
+        .filter(|&d| d != view.direction.opposite() && is_safe(view.grid, step(view.head, d)))
+        .collect()
+}
+
+/// 往 `dir` 走一步后能到达的格子数
+fn room_after(view: &AiView, dir: Direction) -> usize {
+    let start = step(view.head, dir);
+    if !is_safe(view.grid, start) {
+        return 0;
+    }
+    let (width, height) = view.grid.size();
+    let mut seen = vec![false; (width * height) as usize];
+    let index = |(x, y): (i32, i32)| (y * width + x) as usize;
+    seen[index(view.head)] = true;
+    seen[index(start)] = true;
+    let mut queue = VecDeque::from([start]);
+    let mut count = 0;
+    while let Some(pos) = queue.pop_front() {
+        count += 1;
+        for d in DIRECTIONS {
+            let next = step(pos, d);
+            if is_safe(view.grid, next) && !seen[index(next)] {
+                seen[index(next)] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    count
+}
+
+/// 广度优先搜索到 `target` 的最短路径，返回第一步的方向
+fn first_step_towards(view: &AiView, target: (i32, i32)) -> Option<Direction> {
+    let (width, height) = view.grid.size();
+    let index = |(x, y): (i32, i32)| (y * width + x) as usize;
+    // 每个格子记录从蛇头出发的第一步
+    let mut first: Vec<Option<Direction>> = vec![None; (width * height) as usize];
+    let mut queue = VecDeque::new();
+    for dir in safe_directions(view) {
+        let next = step(view.head, dir);
+        first[index(next)] = Some(dir);
+        queue.push_back(next);
+    }
+    while let Some(pos) = queue.pop_front() {
+        if pos == target {
+            return first[index(pos)];
+        }
+        for d in DIRECTIONS {
+            let next = step(pos, d);
+            if next != view.head && is_safe(view.grid, next) && first[index(next)].is_none() {
+                first[index(next)] = first[index(pos)];
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
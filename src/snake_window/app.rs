@@ -0,0 +1,161 @@
+use crate::snake_game::bot::BotDriver;
+use crate::snake_game::events::EventBus;
+use crate::snake_game::game::{Game, GameEvent, Input};
+use crate::snake_game::highscore::HighScores;
+use crate::snake_game::killcam::{KillCam, KillCamPlayback};
+use crate::snake_game::level::Campaign;
+use crate::snake_game::replay::ReplayPlayer;
+use crate::snake_game::save::{delete_save, save_game};
+use crate::snake_game::settings::Settings;
+use crate::snake_window::bindings::{Action, KeyBindings};
+use crate::snake_window::input::key_to_input;
+use crate::snake_window::scenes::board::BoardFx;
+use piston_window::{Glyphs, Key};
+use std::path::Path;
+
+/// 最近一局的回放保存位置
+pub const LAST_REPLAY_PATH: &str = "replays/last.snkr";
+
+/// 进行中的一局的存档位置
+pub const SAVE_PATH: &str = "saves/run.json";
+
+/// 排行榜位置
+pub const HIGH_SCORES_PATH: &str = "saves/highscores.json";
+/// 按键配置位置
+pub const BINDINGS_PATH: &str = "config/bindings.toml";
+/// 设置文件位置
+pub const SETTINGS_PATH: &str = "config/settings.toml";
+/// 默认战役清单
+pub const CAMPAIGN_PATH: &str = "assets/levels/campaign.toml";
+/// 游戏区在屏幕上的边长（像素），格子大小随游戏区的格数缩放
+pub const BOARD_PIXELS: f64 = 600.0;
+/// 排行榜名字的最大长度
+pub const MAX_NAME_LEN: usize = 12;
+/// 双人对战“先吃到N个果子”模式的目标
+pub const VERSUS_FOOD_TARGET: u32 = 10;
+
+/// 各个界面共享的状态：当前这一局、设置、按键、排行榜，以及跨界面的特效
+pub struct App {
+    pub glyphs: Glyphs,
+    pub settings: Settings,
+    pub bindings: KeyBindings,
+    /// 当前这一局
+    pub game: Game,
+    /// 新一局使用的随机种子
+    pub seed: u64,
+    /// 命令行指定的战役，不受“无尽模式”设置影响
+    pub campaign_arg: Option<Campaign>,
+    /// 默认战役，不存在时使用无尽模式
+    pub default_campaign: Option<Campaign>,
+    /// 正在播放的回放
+    pub playback: Option<ReplayPlayer>,
+    /// 控制玩家蛇的外部机器人
+    pub bot: Option<BotDriver>,
+    /// 回放和机器人对局都不动玩家的存档和排行榜
+    pub records_run: bool,
+    /// 是否有可以继续的存档
+    pub has_save: bool,
+    pub event_bus: EventBus,
+    pub high_scores: HighScores,
+    /// 死亡回放：死后先慢动作重放最后几秒，再显示结果
+    pub killcam: KillCam,
+    pub killcam_playback: Option<KillCamPlayback>,
+    /// 本帧收集到的输入，在下一次更新时交给 Game::step
+    pub pending_inputs: Vec<Input>,
+    pub window_width: u32,
+    pub window_height: u32,
+    /// 游戏区左上角坐标
+    pub game_x: f64,
+    pub game_y: f64,
+    /// 背景动画的时间
+    pub bg_time: f64,
+    /// 游戏区上的粒子、鬼字和怪核符号
+    pub fx: BoardFx,
+}
+
+impl App {
+    /// 按当前设置生成新一局的战役
+    pub fn campaign(&self) -> Campaign {
+        match &self.campaign_arg {
+            Some(campaign) => self.settings.apply(campaign),
+            None => self.settings.campaign(self.default_campaign.as_ref()),
+        }
+    }
+
+    /// 按当前设置重新创建还没开始的这一局，回放仍按记录的战役
+    pub fn reset_game(&mut self) {
+        if self.playback.is_none() {
+            self.game = Game::with_campaign(self.campaign(), self.seed);
+        }
+    }
+
+    /// 是否为退出键
+    pub fn is_quit(&self, key: Key) -> bool {
+        self.bindings.action(key) == Some(Action::Quit)
+    }
+
+    /// 把按键转成游戏输入，留到下一次更新
+    pub fn queue_key(&mut self, key: Key) {
+        match key_to_input(&self.bindings, key) {
+            // 机器人对局里方向由机器人决定，键盘只能暂停和重开
+            Some(Input::Turn(_)) if self.bot.is_some() => {}
+            Some(input) => self.pending_inputs.push(input),
+            None => {}
+        }
+    }
+
+    /// 推进这一局：模拟、分发事件、死亡回放和游戏区特效，返回这一帧发生的事件
+    pub fn advance(&mut self, dt: f64) -> Vec<GameEvent> {
+        let events = match (&mut self.playback, &mut self.bot) {
+            (Some(player), _) => player.advance(&mut self.game, dt),
+            (None, Some(bot)) => bot.advance(&mut self.game, dt, &self.pending_inputs),
+            (None, None) => self.game.step(dt, &self.pending_inputs),
+        };
+        self.pending_inputs.clear();
+        self.event_bus.publish(&self.game, &events);
+        self.killcam.observe(&self.game, &events);
+        if let Some(cam) = &mut self.killcam_playback {
+            cam.advance(dt);
+            if cam.is_finished() {
+                self.killcam_playback = None;
+            }
+        }
+        self.fx.on_events(&self.game, &events);
+        self.bg_time += dt;
+        // 死亡粒子与闪烁，死亡回放结束后才开始
+        let show_death = self.game.is_game_over() && self.killcam_playback.is_none();
+        self.fx.update(dt, show_death);
+        events
+    }
+}
+
+/// 保存进行中的一局，游戏已结束时删除存档；双人对战不存档
+pub fn save_run(game: &Game) {
+    if game.get_versus().is_some() {
+        return;
+    }
+    let path = Path::new(SAVE_PATH);
+    let result = if game.is_game_over() {
+        delete_save(path)
+    } else {
+        save_game(game, path)
+    };
+    if let Err(e) = result {
+        eprintln!("保存存档失败: {}", e);
+    }
+}
+
+/// 保存当前这局的回放
+pub fn save_replay(game: &Game) {
+    if let Err(e) = game.get_replay().save(Path::new(LAST_REPLAY_PATH)) {
+        eprintln!("保存回放失败: {}", e);
+    }
+}
+
+/// 按键冲突时在终端提示，按下冲突的按键只会触发排在前面的动作
+pub fn report_conflicts(bindings: &KeyBindings) {
+    for conflict in bindings.conflicts() {
+        let names: Vec<&str> = conflict.actions.iter().map(|a| a.label()).collect();
+        eprintln!("按键 {:?} 同时绑定了 {}，只会触发{}", conflict.key, names.join("、"), names[0]);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::snake_game::game::Input;
+use crate::snake_game::save::write_atomic;
+use crate::snake_snake::snake::Direction;
+use piston_window::Key;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 可以绑定按键的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// 双人对战时第二位玩家的方向
+    Up2,
+    Down2,
+    Left2,
+    Right2,
+    Pause,
+    Restart,
+    /// 退出游戏
+    Quit,
+    /// 回放时暂停/继续
+    ReplayPause,
+    /// 回放时切换快进倍率
+    ReplayFastForward,
+    /// 回放暂停时逐帧前进
+    ReplayStep,
+}
+
+impl Action {
+    /// 全部动作，按按键设置界面上的顺序
+    pub const ALL: [Action; 14] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Up2,
+        Action::Down2,
+        Action::Left2,
+        Action::Right2,
+        Action::Pause,
+        Action::Restart,
+        Action::Quit,
+        Action::ReplayPause,
+        Action::ReplayFastForward,
+        Action::ReplayStep,
+    ];
+
+    /// 显示在按键设置界面上的名字
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Up => "向上",
+            Action::Down => "向下",
+            Action::Left => "向左",
+            Action::Right => "向右",
+            Action::Up2 => "玩家二向上",
+            Action::Down2 => "玩家二向下",
+            Action::Left2 => "玩家二向左",
+            Action::Right2 => "玩家二向右",
+            Action::Pause => "暂停",
+            Action::Restart => "重开",
+            Action::Quit => "退出",
+            Action::ReplayPause => "回放暂停",
+            Action::ReplayFastForward => "回放快进",
+            Action::ReplayStep => "回放逐帧",
+        }
+    }
+
+    /// 对应的游戏输入，退出和回放控制不交给 `Game`
+    pub fn to_input(self) -> Option<Input> {
+        match self {
+            Action::Up => Some(Input::Turn(Direction::Up)),
+            Action::Down => Some(Input::Turn(Direction::Down)),
+            Action::Left => Some(Input::Turn(Direction::Left)),
+            Action::Right => Some(Input::Turn(Direction::Right)),
+            Action::Up2 => Some(Input::Turn2(Direction::Up)),
+            Action::Down2 => Some(Input::Turn2(Direction::Down)),
+            Action::Left2 => Some(Input::Turn2(Direction::Left)),
+            Action::Right2 => Some(Input::Turn2(Direction::Right)),
+            Action::Pause => Some(Input::TogglePause),
+            Action::Restart => Some(Input::Restart),
+            Action::Quit | Action::ReplayPause | Action::ReplayFastForward | Action::ReplayStep => None,
+        }
+    }
+
+    /// 默认按键
+    fn default_keys(self) -> Vec<Key> {
+        let keys: &[Key] = match self {
+            Action::Up => &[Key::Up],
+            Action::Down => &[Key::Down],
+            Action::Left => &[Key::Left],
+            Action::Right => &[Key::Right],
+            Action::Up2 => &[Key::W],
+            Action::Down2 => &[Key::S],
+            Action::Left2 => &[Key::A],
+            Action::Right2 => &[Key::D],
+            Action::Pause => &[Key::P],
+            Action::Restart => &[Key::R],
+            Action::Quit => &[Key::Escape],
+            Action::ReplayPause => &[Key::Space],
+            Action::ReplayFastForward => &[Key::F],
+            Action::ReplayStep => &[Key::N],
+        };
+        keys.to_vec()
+    }
+}
+
+/// 同一个按键绑定到了多个动作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub key: Key,
+    /// 按 `Action::ALL` 的顺序，按下时只触发第一个
+    pub actions: Vec<Action>,
+}
+
+/// 动作到按键的绑定，每个动作可以有多个按键
+///
+/// 配置文件是 TOML，每行一个动作，例如 `pause = ["P", "Space"]`；
+/// 文件里没有写的动作使用默认按键。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KeyBindings {
+    keys: BTreeMap<Action, Vec<Key>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            keys: Action::ALL.iter().map(|&a| (a, a.default_keys())).collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// 读取按键配置，文件不存在时使用默认按键
+    pub fn load(path: &Path) -> io::Result<KeyBindings> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(KeyBindings::default()),
+            Err(e) => return Err(e),
+        };
+        let loaded: KeyBindings = toml::from_str(&text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))?;
+        let mut bindings = KeyBindings::default();
+        bindings.keys.extend(loaded.keys);
+        Ok(bindings)
+    }
+
+    /// 原子地写入按键配置
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let text = toml::to_string_pretty(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        write_atomic(path, text.as_bytes())
+    }
+
+    /// 动作绑定的按键
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.keys.get(&action).map_or(&[], |keys| keys)
+    }
+
+    /// 按键触发的动作，冲突时取 `Action::ALL` 中靠前的那个
+    pub fn action(&self, key: Key) -> Option<Action> {
+        Action::ALL.into_iter().find(|&a| self.keys(a).contains(&key))
+    }
+
+    /// 按键对应的游戏输入
+    pub fn input(&self, key: Key) -> Option<Input> {
+        self.action(key).and_then(Action::to_input)
+    }
+
+    /// 把动作改绑为只有 `key` 一个按键
+    pub fn bind(&mut self, action: Action, key: Key) {
+        self.keys.insert(action, vec![key]);
+    }
+
+    /// 给动作追加一个按键
+    pub fn add(&mut self, action: Action, key: Key) {
+        let keys = self.keys.entry(action).or_default();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    /// 恢复动作的默认按键
+    pub fn reset(&mut self, action: Action) {
+        self.keys.insert(action, action.default_keys());
+    }
+
+    /// 找出绑定到多个动作的按键
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut by_key: BTreeMap<Key, Vec<Action>> = BTreeMap::new();
+        for action in Action::ALL {
+            for &key in self.keys(action) {
+                let actions = by_key.entry(key).or_default();
+                if !actions.contains(&action) {
+                    actions.push(action);
+                }
+            }
+        }
+        by_key
+            .into_iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .map(|(key, actions)| Conflict { key, actions })
+            .collect()
+    }
+
+    /// 动作是否和别的动作共用了按键
+    pub fn is_conflicting(&self, action: Action) -> bool {
+        self.conflicts().iter().any(|c| c.actions.contains(&action))
+    }
+
+    /// 动作的按键写成文字，例如 `P / Space`，没有绑定时为“未绑定”
+    pub fn describe(&self, action: Action) -> String {
+        let keys = self.keys(action);
+        if keys.is_empty() {
+            return "未绑定".to_string();
+        }
+        keys.iter().map(|k| format!("{:?}", k)).collect::<Vec<_>>().join(" / ")
+    }
+
+    /// 动作的第一个按键，用于界面上的提示
+    pub fn hint(&self, action: Action) -> String {
+        self.keys(action).first().map_or_else(|| "未绑定".to_string(), |k| format!("{:?}", k))
+    }
+}
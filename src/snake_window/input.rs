@@ -0,0 +1,19 @@
+use crate::snake_game::game::Input;
+use crate::snake_game::replay::ReplayPlayer;
+use crate::snake_window::bindings::{Action, KeyBindings};
+use piston_window::Key;
+
+/// 按当前的按键绑定将 piston 按键映射为游戏输入
+pub fn key_to_input(bindings: &KeyBindings, key: Key) -> Option<Input> {
+    bindings.input(key)
+}
+
+/// 回放模式下的按键：默认空格暂停/继续，F 切换快进倍率，N 暂停时逐帧前进
+pub fn handle_replay_key(bindings: &KeyBindings, player: &mut ReplayPlayer, key: Key) {
+    match bindings.action(key) {
+        Some(Action::ReplayPause) => player.toggle_pause(),
+        Some(Action::ReplayFastForward) => player.fast_forward(),
+        Some(Action::ReplayStep) => player.request_step(),
+        _ => {}
+    }
+}
@@ -1 +1,6 @@
+pub mod app;
+pub mod bindings;
 pub mod draw;
+pub mod input;
+pub mod render;
+pub mod scenes;
@@ -0,0 +1,368 @@
+use crate::snake_game::game::{Game, AI_TELEGRAPH_TIME};
+use crate::snake_game::net::Snapshot;
+use crate::snake_snake::snake::{AISnake, Snake};
+use crate::snake_window::draw::{draw_block, draw_rectangle};
+use piston_window::rectangle::Shape;
+use piston_window::types::Color;
+use piston_window::{Context, G2d, Glyphs};
+use rand::Rng;
+
+/// 上边框颜色
+const T_BORDER_COLOR: Color = [0.0000, 0.5, 0.5, 0.6];
+/// 下边框颜色
+const B_BORDER_COLOR: Color = [0.0000, 0.5, 0.5, 0.6];
+/// 左边框颜色
+const L_BORDER_COLOR: Color = [0.0000, 0.5, 0.5, 0.6];
+/// 右边框颜色
+const R_BORDER_COLOR: Color = [0.0000, 0.5, 0.5, 0.6];
+
+///游戏结束颜色
+const GAMEOVER_COLOR: Color = [0.90, 0.00, 0.00, 0.5];
+
+/// 玩家蛇的彩虹蛇身
+const RAINBOW: [Color; 7] = [
+    [1.0, 0.2, 0.2, 1.0], // 红
+    [1.0, 0.7, 0.2, 1.0], // 橙
+    [1.0, 1.0, 0.2, 1.0], // 黄
+    [0.2, 1.0, 0.2, 1.0], // 绿
+    [0.2, 0.7, 1.0, 1.0], // 青
+    [0.4, 0.2, 1.0, 1.0], // 蓝
+    [1.0, 0.2, 1.0, 1.0], // 紫
+];
+/// 第二位玩家的蛇身：褪色的病房粉与灰绿
+const PLAYER_TWO_PALETTE: [Color; 4] = [
+    [0.95, 0.6, 0.7, 1.0],
+    [0.7, 0.85, 0.75, 1.0],
+    [0.85, 0.5, 0.6, 1.0],
+    [0.6, 0.75, 0.7, 1.0],
+];
+/// 噩梦感蛇头主色：苍白带青紫
+const PLAYER_ONE_HEAD: Color = [0.7, 0.8, 1.0, 1.0];
+/// 第二位玩家的蛇头：苍白带粉
+const PLAYER_TWO_HEAD: Color = [1.0, 0.8, 0.85, 1.0];
+
+/// 游戏绘制
+pub fn draw_game(game: &Game, con: &Context, g: &mut G2d, time: f64, glyphs: &mut Glyphs) {
+    let (width, height) = game.get_size();
+    draw_snake(game.get_snake(), con, g, time); // 玩家蛇不需要glyphs
+    for player in game.get_players().iter().filter(|p| p.is_active()) {
+        draw_player_snake(player.snake(), PLAYER_TWO_HEAD, &PLAYER_TWO_PALETTE, con, g, time);
+    }
+    for ai in &game.ai_snakes {
+        // 残影
+        let mut fade = 0.4;
+        for block in ai.body.iter().skip(1).take(4) {
+            draw_block([0.7, 0.0, 0.0, fade], Shape::Round(12.5, 16), block.x, block.y, con, g);
+            fade *= 0.6;
+        }
+        // 恐怖高光
+        let (hx, hy) = ai.head_position();
+        draw_block([1.0, 0.0, 0.0, 0.7], Shape::Round(6.0, 16), hx, hy, con, g);
+        draw_ai_snake(ai, con, g, time); // AI蛇不需要glyphs
+    }
+    // AI蛇的残骸果子
+    for &(x, y) in game.get_leftover_food() {
+        let pulse = 0.7 + 0.3 * (time * 4.0 + (x + y) as f64).sin().abs() as f32;
+        draw_block([0.55, 0.05, 0.1, pulse], Shape::Round(7.0, 16), x, y, con, g);
+    }
+    // AI蛇出生预警：越接近出现闪得越快越亮
+    for t in game.get_ai_telegraphs() {
+        let progress = (1.0 - t.remaining / AI_TELEGRAPH_TIME).clamp(0.0, 1.0);
+        let blink = ((time * (6.0 + 18.0 * progress)).sin() * 0.5 + 0.5) as f32;
+        let alpha = 0.1 + 0.5 * blink * progress as f32;
+        for dx in 0..3 {
+            draw_block([t.color_head[0], t.color_head[1], t.color_head[2], alpha], Shape::Round(10.0, 16), t.x + dx, t.y, con, g);
+        }
+    }
+    if let Some((food_x, food_y)) = game.get_food() {
+        // 怪核符号果
+        draw_weirdcore_food(food_x, food_y, con, g, time, glyphs);
+    }
+    // 绘制AI蛇油滴粒子
+    use piston_window::ellipse;
+    for p in &game.ai_oil_particles {
+        let alpha = ((p.life / p.max_life) as f32).min(1.0) * 0.7;
+        let size = 6.0 * (p.life / p.max_life).max(0.4);
+        ellipse([0.08, 0.08, 0.08, alpha], [p.x - size/2.0, p.y - size/2.0, size, size * 1.2], con.transform, g);
+    }
+    // 绘制障碍物（深灰色）
+    let obstacle_color: Color = [0.2, 0.2, 0.2, 1.0];
+    for &(ox, oy) in game.get_obstacles() {
+        draw_block(obstacle_color, Shape::Square, ox, oy, con, g);
+    }
+    draw_border(width, height, con, g);
+
+    // 如果游戏失败 绘制游戏失败画面
+    if game.is_game_over() {
+        draw_rectangle(GAMEOVER_COLOR, 0, 0, width, height, con, g);
+    }
+}
+
+/// 联机时绘制服务器发来的局面，自己（第 `me` 位玩家）用彩虹色，其他玩家用第二位玩家的颜色
+pub fn draw_snapshot(snapshot: &Snapshot, me: usize, (width, height): (i32, i32), con: &Context, g: &mut G2d, time: f64) {
+    for p in &snapshot.players {
+        if p.player == me {
+            draw_snake(&p.snake, con, g, time);
+        } else {
+            draw_player_snake(&p.snake, PLAYER_TWO_HEAD, &PLAYER_TWO_PALETTE, con, g, time);
+        }
+    }
+    for ai in &snapshot.ai_snakes {
+        draw_ai_snake(ai, con, g, time);
+    }
+    for &(x, y) in &snapshot.leftover_food {
+        draw_block([0.55, 0.05, 0.1, 0.9], Shape::Round(7.0, 16), x, y, con, g);
+    }
+    for &(x, y) in &snapshot.telegraphs {
+        let blink = ((time * 12.0).sin() * 0.5 + 0.5) as f32;
+        for dx in 0..3 {
+            draw_block([0.95, 0.93, 0.78, 0.1 + 0.4 * blink], Shape::Round(10.0, 16), x + dx, y, con, g);
+        }
+    }
+    if let Some((x, y)) = snapshot.food {
+        draw_block([0.9, 0.1, 0.3, 1.0], Shape::Round(10.0, 16), x, y, con, g);
+    }
+    for &(ox, oy) in snapshot.obstacles.iter().flatten() {
+        draw_block([0.2, 0.2, 0.2, 1.0], Shape::Square, ox, oy, con, g);
+    }
+    draw_border(width, height, con, g);
+}
+
+/// 游戏区的四条边框
+fn draw_border(width: i32, height: i32, con: &Context, g: &mut G2d) {
+    //上边框
+    draw_rectangle(T_BORDER_COLOR, 0, 0, width, 1, con, g);
+    // 下边框
+    draw_rectangle(B_BORDER_COLOR, 0, height - 1, width, 1, con, g);
+    // 左边框
+    draw_rectangle(L_BORDER_COLOR, 0, 1, 1, height - 2, con, g);
+    // 右边框
+    draw_rectangle(
+        R_BORDER_COLOR,
+        width - 1,
+        1,
+        1,
+        height - 2,
+        con,
+        g,
+    );
+}
+
+/// 玩家蛇的绘制
+pub fn draw_snake(snake: &Snake, con: &Context, g: &mut G2d, time: f64) {
+    draw_player_snake(snake, PLAYER_ONE_HEAD, &RAINBOW, con, g, time);
+}
+
+/// 按指定的蛇头颜色和蛇身调色板绘制玩家蛇
+fn draw_player_snake(snake: &Snake, head_color: Color, palette: &[Color], con: &Context, g: &mut G2d, time: f64) {
+    let mut is_head = true;
+    let mut idx = 0;
+    for block in snake.body() {
+        if is_head {
+            is_head = false;
+            // 抖动偏移
+            let shake_x = (time * 8.0).sin() * 1.5 + (time * 3.3).cos() * 1.0;
+            let shake_y = (time * 7.0).cos() * 1.2 + (time * 2.1).sin() * 0.8;
+            let base_x = (block.x as f64) * 20.0 + shake_x;
+            let base_y = (block.y as f64) * 20.0 + shake_y;
+            use piston_window::{ellipse, line};
+            // 蛇头
+            draw_block(
+                head_color,
+                Shape::Round(10.0, 16),
+                block.x,
+                block.y,
+                con,
+                g,
+            );
+            // 蛇头高光
+            draw_block([0.9, 0.95, 1.0, 0.5], Shape::Round(5.0, 16), block.x, block.y, con, g);
+            // 眼睛参数
+            let eye_w = 6.0;
+            let eye_h = 8.0;
+            let eye_y = base_y + 6.0;
+            // 左眼
+            ellipse([0.2, 0.2, 0.3, 1.0], [base_x + 3.0, eye_y, eye_w, eye_h], con.transform, g); // 黑眼圈
+            ellipse([0.85, 0.9, 1.0, 1.0], [base_x + 4.0, eye_y + 1.0, 4.0, 6.0], con.transform, g); // 眼白
+            ellipse([0.5, 0.6, 0.8, 1.0], [base_x + 5.5, eye_y + 3.0, 1.5, 2.0], con.transform, g); // 泪痕
+            // 右眼
+            ellipse([0.2, 0.2, 0.3, 1.0], [base_x + 11.0, eye_y, eye_w, eye_h], con.transform, g); // 黑眼圈
+            ellipse([0.85, 0.9, 1.0, 1.0], [base_x + 12.0, eye_y + 1.0, 4.0, 6.0], con.transform, g); // 眼白
+            ellipse([0.5, 0.6, 0.8, 1.0], [base_x + 13.5, eye_y + 3.0, 1.5, 2.0], con.transform, g); // 泪痕
+            // 嘴巴（下垂弧线）
+            line(
+                [0.4, 0.3, 0.5, 1.0],
+                2.0,
+                [base_x + 7.0, base_y + 16.0, base_x + 13.0, base_y + 18.0],
+                con.transform,
+                g,
+            );
+        } else {
+            let color = palette[idx % palette.len()];
+            draw_block(
+                color,
+                Shape::Round(12.5, 16),
+                block.x,
+                block.y,
+                con,
+                g,
+            );
+            // 蛇身高光
+            draw_block([1.0, 1.0, 1.0, 0.3], Shape::Round(6.0, 16), block.x, block.y, con, g);
+            idx += 1;
+        }
+    }
+}
+
+/// AI蛇的绘制
+pub fn draw_ai_snake(ai: &AISnake, con: &Context, g: &mut G2d, time: f64) {
+    // 动态错位参数
+    let twitch_period = 2.0; // 每2秒一次
+    let twitch_phase = (time + (ai.head_position().0 as f64) * 0.37 + (ai.head_position().1 as f64) * 0.21) % twitch_period;
+    let twitching = twitch_phase < 0.08; // 持续约0.08秒
+    let mut twitch_idx = 0; // 哪一节抽搐
+    if twitching {
+        // 随机选一节（头或身）
+        let n = ai.body.len().min(4);
+        if n > 0 {
+            twitch_idx = ((time * 13.7).sin().abs() * (n as f64)).floor() as usize;
+        }
+    }
+    for (idx, block) in ai.body.iter().enumerate() {
+        let mut offset_x = 0.0;
+        let mut offset_y = 0.0;
+        if twitching && idx == twitch_idx {
+            offset_x = (time * 20.0).sin() * 3.0 + (time * 7.0).cos() * 2.0;
+            offset_y = (time * 17.0).cos() * 2.0 + (time * 5.0).sin() * 1.5;
+        }
+        if idx == 0 {
+            let base_x = (block.x as f64) * 20.0 + offset_x;
+            let base_y = (block.y as f64) * 20.0 + offset_y;
+            use piston_window::{ellipse, line};
+            // 蛇头
+            draw_block(
+                ai.color_head,
+                Shape::Round(10.0, 16),
+                block.x,
+                block.y,
+                con,
+                g,
+            );
+            // 头部高光
+            draw_block([1.0, 1.0, 0.95, 0.4], Shape::Round(5.0, 16), block.x, block.y, con, g);
+            // 一大一小错位眼睛
+            // 左眼（大，略高）
+            ellipse([0.15, 0.18, 0.22, 1.0], [base_x + 2.0, base_y + 5.0, 7.0, 9.0], con.transform, g); // 眼圈
+            ellipse([0.95, 0.98, 1.0, 1.0], [base_x + 3.5, base_y + 6.5, 4.5, 6.0], con.transform, g); // 眼白
+            ellipse([0.18, 0.18, 0.22, 1.0], [base_x + 5.0, base_y + 9.0, 2.0, 2.5], con.transform, g); // 瞳孔
+            // 黑色泪痕
+            ellipse([0.08, 0.08, 0.08, 0.7], [base_x + 6.0, base_y + 13.0, 1.2, 3.5], con.transform, g);
+            // 右眼（小，略低，错位）
+            ellipse([0.15, 0.18, 0.22, 1.0], [base_x + 11.0, base_y + 8.0, 5.0, 6.0], con.transform, g); // 眼圈
+            ellipse([0.95, 0.98, 1.0, 1.0], [base_x + 12.0, base_y + 9.0, 2.8, 3.5], con.transform, g); // 眼白
+            ellipse([0.18, 0.18, 0.22, 1.0], [base_x + 13.0, base_y + 11.0, 1.0, 1.3], con.transform, g); // 瞳孔
+            // 嘴巴：不对称假笑
+            line(
+                [0.25, 0.18, 0.22, 1.0],
+                2.0,
+                [base_x + 7.0, base_y + 16.0, base_x + 15.0, base_y + 14.0],
+                con.transform,
+                g,
+            );
+            // 嘴角裂口
+            line(
+                [0.18, 0.08, 0.08, 1.0],
+                1.0,
+                [base_x + 15.0, base_y + 14.0, base_x + 17.0, base_y + 16.0],
+                con.transform,
+                g,
+            );
+            // 头部缝线
+            line(
+                [0.18, 0.18, 0.22, 0.7],
+                1.0,
+                [base_x + 10.0, base_y + 4.0, base_x + 10.0, base_y + 16.0],
+                con.transform,
+                g,
+            );
+            for i in 0..4 {
+                let y = base_y + 6.0 + i as f64 * 2.5;
+                line(
+                    [0.18, 0.18, 0.22, 0.7],
+                    1.0,
+                    [base_x + 9.0, y, base_x + 11.0, y + 1.0],
+                    con.transform,
+                    g,
+                );
+            }
+        } else {
+            draw_block(
+                ai.color_body,
+                Shape::Round(12.5, 16),
+                block.x,
+                block.y,
+                con,
+                g,
+            );
+        }
+    }
+}
+
+// 怪核符号池
+const WEIRDCORE_SYMBOLS: [(&str, [f32; 4]); 8] = [
+    ("?", [0.9, 0.9, 0.2, 1.0]),
+    ("!", [1.0, 0.2, 0.2, 1.0]),
+    ("EXIT", [0.7, 0.7, 0.7, 1.0]),
+    ("ERROR", [0.8, 0.2, 0.8, 1.0]),
+    ("鬼", [0.9, 0.0, 0.0, 1.0]),
+    ("眼", [0.7, 0.7, 1.0, 1.0]),
+    ("门", [0.6, 0.6, 0.8, 1.0]),
+    ("手", [0.8, 0.8, 0.8, 1.0]),
+];
+
+/// 怪核符号果绘制
+pub fn draw_weirdcore_food(x: i32, y: i32, con: &Context, g: &mut G2d, time: f64, glyphs: &mut Glyphs) {
+    use piston_window::{ellipse, line, Transformed};
+    let mut rng = rand::thread_rng();
+    // 1. 选取符号和主色（随时间变化）
+    let idx = ((time * 0.7).sin().abs() * (WEIRDCORE_SYMBOLS.len() as f64)).floor() as usize % WEIRDCORE_SYMBOLS.len();
+    let (ch, color) = WEIRDCORE_SYMBOLS[idx];
+    // 2. 动态参数
+    let base_x = (x as f64) * 20.0;
+    let base_y = (y as f64) * 20.0;
+    let scale = 1.0 + 0.13 * (time * 1.7).sin() + 0.07 * (time * 2.9).cos();
+    let rot = (time * 1.2).sin() * 0.18;
+    let alpha = 0.85 + 0.15 * (time * 3.1).cos();
+    // 3. 光晕/阴影
+    let glow_color = [color[0], color[1], color[2], 0.18 + 0.12 * (time * 2.7).sin().abs() as f32];
+    ellipse(glow_color, [base_x - 8.0, base_y - 8.0, 36.0, 36.0], con.transform, g);
+    // 4. 主体符号
+    let font_size = if ch.len() > 2 { 18 } else { 28 };
+    let symbol_color = [color[0], color[1], color[2], alpha as f32];
+    let transform = con.transform.trans(base_x + 10.0, base_y + 10.0).rot_rad(rot).scale(scale, scale);
+    piston_window::text(symbol_color, font_size, ch, glyphs, transform, g).ok();
+    // 5. 噪点/裂缝
+    for i in 0..rng.gen_range(3..7) {
+        let angle = time * 2.0 + i as f64 * 1.3;
+        let r = 10.0 + 6.0 * (angle * 1.2).sin();
+        let px = base_x + 10.0 + r * (angle).cos();
+        let py = base_y + 10.0 + r * (angle).sin();
+        let dot_alpha = 0.18 + 0.18 * (angle * 1.7).sin().abs() as f32;
+        ellipse([0.08, 0.08, 0.08, dot_alpha], [px, py, 2.0, 2.0], con.transform, g);
+        if i % 2 == 0 {
+            // 裂缝
+            let x2 = px + 2.0 * (angle * 2.1).cos();
+            let y2 = py + 2.0 * (angle * 2.1).sin();
+            line([0.08, 0.08, 0.08, dot_alpha], 1.0, [px, py, x2, y2], con.transform, g);
+        }
+    }
+    // 6. 符号碎片/漂浮点
+    for i in 0..rng.gen_range(2..5) {
+        let angle = time * 1.3 + i as f64 * 2.2;
+        let r = 18.0 + 8.0 * (angle * 1.1).cos();
+        let px = base_x + 10.0 + r * (angle).cos();
+        let py = base_y + 10.0 + r * (angle).sin();
+        let frag_alpha = 0.10 + 0.10 * (angle * 1.9).sin().abs() as f32;
+        ellipse([color[0], color[1], color[2], frag_alpha], [px, py, 2.5, 2.5], con.transform, g);
+    }
+}
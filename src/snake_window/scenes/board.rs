@@ -0,0 +1,585 @@
+use crate::snake_game::game::{Game, GameEvent, VersusMode};
+use crate::snake_game::killcam::KILLCAM_SPEED;
+use crate::snake_window::app::{App, BOARD_PIXELS};
+use crate::snake_window::bindings::Action;
+use crate::snake_window::render::draw_game;
+use piston_window::{ellipse, line, polygon, rectangle, Context, G2d, Transformed};
+use rand::Rng;
+
+// 星星结构体
+struct Star {
+    x: f64,
+    y: f64,
+    speed: f64,
+    size: f64,
+}
+
+// 粒子结构体
+struct Particle {
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+    life: f64,
+}
+
+// 鬼字变形状态
+struct GhostDeform {
+    scale: f64,
+    angle: f64,
+    color: [f32; 4],
+    tear: bool,
+}
+
+// 怪核符号结构体
+#[derive(Clone)]
+struct WeirdcoreSymbol {
+    ch: &'static str,
+    x: f64,
+    y: f64,
+    angle: f64,
+    scale: f64,
+    alpha: f32,
+    life: f64,
+    max_life: f64,
+    color: [f32; 4],
+}
+
+// 怪核符号池
+const WEIRDCORE_SYMBOL_POOL: [(&str, [f32; 4]); 8] = [
+    ("?", [0.9, 0.9, 0.2, 1.0]),
+    ("!", [1.0, 0.2, 0.2, 1.0]),
+    ("EXIT", [0.7, 0.7, 0.7, 1.0]),
+    ("ERROR", [0.8, 0.2, 0.8, 1.0]),
+    ("鬼", [0.9, 0.0, 0.0, 1.0]),
+    ("眼", [0.7, 0.7, 1.0, 1.0]),
+    ("门", [0.6, 0.6, 0.8, 1.0]),
+    ("手", [0.8, 0.8, 0.8, 1.0]),
+];
+
+/// 游戏区上的特效：星空、死亡粒子与闪光、鬼字变形、AI蛇产卵粒子和怪核符号
+///
+/// 游戏中、暂停、游戏结束等界面共用同一份，切换界面时特效不会中断。
+pub struct BoardFx {
+    stars: Vec<Star>,
+    particles: Vec<Particle>,
+    flash_timer: f64,
+    ghost_deforms: Vec<GhostDeform>,
+    ghost_deform_timer: f64,
+    ai_egg_particles: Vec<(f64, f64, f64, f64, f64)>,
+    weirdcore_symbols: Vec<WeirdcoreSymbol>,
+    last_weirdcore_time: f64,
+}
+
+impl Default for BoardFx {
+    fn default() -> BoardFx {
+        // 星空初始化
+        let stars = (0..50)
+            .map(|_| {
+                let mut rng = rand::thread_rng();
+                Star {
+                    x: rng.gen_range(0.0..600.0),
+                    y: rng.gen_range(0.0..600.0),
+                    speed: rng.gen_range(10.0..40.0),
+                    size: rng.gen_range(1.0..2.5),
+                }
+            })
+            .collect();
+        BoardFx {
+            stars,
+            particles: Vec::new(),
+            flash_timer: 0.0,
+            ghost_deforms: vec![],
+            ghost_deform_timer: 0.0,
+            ai_egg_particles: Vec::new(),
+            weirdcore_symbols: Vec::new(),
+            last_weirdcore_time: 0.0,
+        }
+    }
+}
+
+impl BoardFx {
+    /// 根据这一帧的事件生成粒子
+    pub fn on_events(&mut self, game: &Game, events: &[GameEvent]) {
+        for ev in events {
+            match *ev {
+                // AI蛇产卵爆炸粒子
+                GameEvent::EggLaid { x, y } => {
+                    let mut rng = rand::thread_rng();
+                    for _ in 0..18 {
+                        let angle = rng.gen_range(0.0..std::f64::consts::PI * 2.0);
+                        let speed = rng.gen_range(40.0..120.0);
+                        let vx = speed * angle.cos();
+                        let vy = speed * angle.sin();
+                        let k = board_scale(game) * 20.0;
+                        self.ai_egg_particles.push((x as f64 * k + k / 2.0, y as f64 * k + k / 2.0, vx, vy, 0.7));
+                    }
+                }
+                // 结束这一局的死亡在蛇头处溅出血红色粒子并闪烁
+                GameEvent::Died { pos: (x, y), .. } if game.is_game_over() && self.particles.is_empty() => {
+                    let k = board_scale(game) * 20.0;
+                    let (cx, cy) = ((x as f64) * k + k / 2.0, (y as f64) * k + k / 2.0);
+                    let mut rng = rand::thread_rng();
+                    for _ in 0..60 {
+                        let angle = rng.gen_range(0.0..std::f64::consts::PI * 2.0);
+                        let speed = rng.gen_range(80.0..180.0);
+                        let vx = speed * angle.cos();
+                        let vy = speed * angle.sin();
+                        self.particles.push(Particle {
+                            x: cx,
+                            y: cy,
+                            vx,
+                            vy,
+                            life: 1.2,
+                        });
+                    }
+                    self.flash_timer = 0.5;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 推进星空、死亡粒子和鬼字变形；`show_death` 为假时清掉死亡粒子
+    pub fn update(&mut self, dt: f64, show_death: bool) {
+        for star in &mut self.stars {
+            star.y += star.speed * dt;
+            if star.y > 600.0 {
+                let mut rng = rand::thread_rng();
+                star.y = 0.0;
+                star.x = rng.gen_range(0.0..600.0);
+                star.size = rng.gen_range(1.0..2.5);
+                star.speed = rng.gen_range(10.0..40.0);
+            }
+        }
+        if show_death {
+            // 粒子运动
+            for p in &mut self.particles {
+                p.x += p.vx * dt;
+                p.y += p.vy * dt;
+                p.life -= dt;
+            }
+            self.particles.retain(|p| p.life > 0.0);
+            // 闪烁计时
+            if self.flash_timer > 0.0 {
+                self.flash_timer -= dt;
+            }
+        } else {
+            self.particles.clear();
+            self.flash_timer = 0.0;
+        }
+        // 鬼字变形定时器
+        self.ghost_deform_timer += dt;
+        if self.ghost_deform_timer > 1.2 {
+            self.ghost_deform_timer = 0.0;
+            let mut rng = rand::thread_rng();
+            for deform in &mut self.ghost_deforms {
+                if rng.gen_bool(0.25) {
+                    deform.scale = rng.gen_range(0.8..1.3);
+                    deform.angle = rng.gen_range(-0.4..0.4);
+                    let c = rng.gen_range(0.7..1.0) as f32;
+                    deform.color = [c, 0.0, 0.0, 1.0];
+                    deform.tear = rng.gen_bool(0.18);
+                }
+            }
+        }
+    }
+}
+
+/// 游戏区绘制时的缩放：`draw_game` 按每格 20 像素画，游戏区不是 30 格时缩放到 `BOARD_PIXELS`
+pub fn board_scale(game: &Game) -> f64 {
+    let (width, height) = game.get_size();
+    BOARD_PIXELS / (20.0 * width.max(height) as f64)
+}
+
+/// 绘制游戏中的完整画面：背景、游戏区、边框、顶部和底部的提示，死亡回放时画回放中的局面
+pub fn draw_board(app: &mut App, c: Context, g: &mut G2d) {
+    let (window_width, window_height) = (app.window_width, app.window_height);
+    let (game_x, game_y, bg_time) = (app.game_x, app.game_y, app.bg_time);
+    let (game, settings, bindings) = (&app.game, &app.settings, &app.bindings);
+    let (playback, bot, killcam_playback) = (&app.playback, &app.bot, &app.killcam_playback);
+    let glyphs = &mut app.glyphs;
+    let fx = &mut app.fx;
+    // 恐怖背景（递增）
+    let t = (bg_time * 0.1).sin() * 0.5 + 0.5;
+    let level = game.level;
+    let base = 0.2 + 0.1 * (level as f32).min(5.0);
+    let red = base + 0.2 * t as f32 + 0.08 * (level as f32);
+    let green = base * (1.0 - 0.08 * (level as f32));
+    let blue = base * (1.0 - 0.12 * (level as f32));
+    for i in 0..40 {
+        let k = i as f32 / 39.0;
+        let color = [
+            red * (1.0 - k) + blue * k,
+            green * (1.0 - k) + red * k,
+            blue * (1.0 - k) + red * k,
+            1.0,
+        ];
+        rectangle(color, [0.0, i as f64 * 20.0, window_width as f64, 20.0], c.transform, g);
+    }
+    // 游戏区外半透明黑色分隔带
+    rectangle([0.0, 0.0, 0.0, 0.45], [0.0, 0.0, window_width as f64, game_y], c.transform, g); // 顶部
+    rectangle([0.0, 0.0, 0.0, 0.45], [0.0, game_y + 600.0, window_width as f64, window_height as f64 - (game_y + 600.0)], c.transform, g); // 底部
+    // 游戏区血色发光边框
+    let border_glow = [0.8, 0.0, 0.0, 0.18];
+    rectangle(border_glow, [game_x-12.0, game_y-12.0, 624.0, 24.0], c.transform, g); // 上
+    rectangle(border_glow, [game_x-12.0, game_y-12.0, 24.0, 624.0], c.transform, g); // 左
+    rectangle(border_glow, [game_x-12.0, game_y+588.0, 624.0, 24.0], c.transform, g); // 下
+    rectangle(border_glow, [game_x+588.0, game_y-12.0, 24.0, 624.0], c.transform, g); // 右
+    // 游戏区立体边框
+    let border_light = [0.9, 0.9, 0.9, 1.0];
+    let border_dark = [0.2, 0.0, 0.0, 1.0];
+    rectangle(border_light, [game_x, game_y, 600.0, 8.0], c.transform, g); // 上
+    rectangle(border_light, [game_x, game_y, 8.0, 600.0], c.transform, g); // 左
+    rectangle(border_dark, [game_x, game_y+592.0, 600.0, 8.0], c.transform, g); // 下
+    rectangle(border_dark, [game_x+592.0, game_y, 8.0, 600.0], c.transform, g); // 右
+    // 游戏区内容平移
+    // ====== 梦核/怪核全局画面抽搐与色彩扰动 ======
+    let shake_period = 4.4; // 频率减半
+    let shake_phase = (bg_time % shake_period) / shake_period;
+    let shaking = settings.effects > 0.0 && shake_phase < 0.08; // 持续时间也略缩短
+    let mut shake_x = 0.0;
+    let mut shake_y = 0.0;
+    let mut shake_scale = 1.0;
+    let mut shake_rot = 0.0;
+    if shaking {
+        // 画面抽搐参数（幅度减小）
+        let t = shake_phase * std::f64::consts::PI * 2.0;
+        let effects = settings.effects;
+        shake_x = ((bg_time * 23.0).sin() * 3.5 + (bg_time * 7.0).cos() * 1.5) * effects;
+        shake_y = ((bg_time * 17.0).cos() * 2.5 + (bg_time * 11.0).sin() * 1.2) * effects;
+        shake_scale = 1.0 + (t * 2.0).sin() * 0.012 * effects;
+        shake_rot = (t * 1.3).sin() * 0.018 * effects;
+    }
+    // 死亡回放时画回放中的局面
+    let shown = killcam_playback.as_ref().map_or(game, |cam| cam.game());
+    let k = board_scale(shown);
+    let c_game = &c.trans(game_x + shake_x, game_y + shake_y)
+        .rot_rad(shake_rot)
+        .scale(shake_scale * k, shake_scale * k);
+    // 伪模糊/重影：抽搐时多绘制1~2层错位半透明内容
+    if shaking {
+        for i in 0..2 {
+            let offset = 2.0 + i as f64 * 1.2;
+            let scale = shake_scale * (1.0 + 0.004 * (i as f64 + 1.0)) * k;
+            let rot = shake_rot + (i as f64 + 1.0) * 0.007;
+            let c_blur = &c.trans(game_x + shake_x + offset, game_y + shake_y - offset)
+                .rot_rad(rot)
+                .scale(scale, scale);
+            draw_game(shown, c_blur, g, bg_time, glyphs);
+        }
+    }
+    draw_game(shown, c_game, g, bg_time, glyphs);
+    // 色彩扰动
+    if shaking {
+        let color_shift = [
+            0.3 + 0.2 * (bg_time * 2.0).sin() as f32,
+            0.1 + 0.3 * (bg_time * 1.3).cos() as f32,
+            0.4 + 0.2 * (bg_time * 1.7).sin() as f32,
+            (0.18 + 0.18 * (shake_phase as f32)) * settings.effects as f32,
+        ];
+        rectangle(color_shift, [game_x, game_y, 600.0, 600.0], c.transform, g);
+    }
+    // 在每个障碍物上绘制呼吸光效和红色“鬼”字（带变形）
+    let breath = ((bg_time * 2.0).sin() * 0.5 + 0.5) as f32; // 0~1
+    let obs = game.get_obstacles();
+    // 初始化变形状态
+    if fx.ghost_deforms.len() != obs.len() {
+        fx.ghost_deforms = obs.iter().map(|_| GhostDeform {
+            scale: 1.0,
+            angle: 0.0,
+            color: [1.0, 0.0, 0.0, 1.0],
+            tear: false,
+        }).collect();
+    }
+    for (i, &(ox, oy)) in obs.iter().enumerate() {
+        // 呼吸光圈
+        let x = (ox as f64) * 20.0 * k + game_x;
+        let y = (oy as f64) * 20.0 * k + game_y;
+        let glow_color = [1.0, 0.3, 0.3, 0.18 + 0.22 * breath];
+        let glow_size = (28.0 + 8.0 * breath as f64) * k;
+        ellipse(glow_color, [x + 10.0 * k - glow_size/2.0, y + 10.0 * k - glow_size/2.0, glow_size, glow_size], c.transform, g);
+        // 变形参数
+        let deform = &fx.ghost_deforms[i];
+        let tx = x + (2.0 + 8.0 * (1.0 - deform.scale)) * k; // 缩放时居中
+        let ty = y + 18.0 * k;
+        let transform_ghost = c.transform.trans(tx, ty)
+            .rot_rad(deform.angle)
+            .scale(deform.scale * k, deform.scale * k);
+        piston_window::text(deform.color, 16, "鬼", glyphs, transform_ghost, g).ok();
+        // 流泪
+        if deform.tear {
+            let tear_x = x + 10.0 * k;
+            let tear_y = y + 26.0 * k;
+            ellipse([0.8, 0.0, 0.0, 0.8], [tear_x-2.0, tear_y, 4.0, 6.0], c.transform, g);
+        }
+    }
+    // 死亡回放：暗红边缘和提示
+    if killcam_playback.is_some() {
+        let pulse = 0.15 + 0.1 * (bg_time * 3.0).sin().abs() as f32;
+        rectangle([0.5, 0.0, 0.0, pulse], [game_x, game_y, 600.0, 12.0], c.transform, g);
+        rectangle([0.5, 0.0, 0.0, pulse], [game_x, game_y + 588.0, 600.0, 12.0], c.transform, g);
+        let cam_text = format!("死亡回放 x{}  按任意键跳过", KILLCAM_SPEED);
+        piston_window::text([1.0, 0.2, 0.2, 1.0], 24, &cam_text, glyphs, c.transform.trans(game_x + 160.0, game_y + 40.0), g).ok();
+    }
+    // AI蛇产卵爆炸粒子
+    fx.ai_egg_particles.iter_mut().for_each(|p| {
+        p.0 += p.2 * 0.016;
+        p.1 += p.3 * 0.016;
+        p.4 -= 0.016;
+    });
+    fx.ai_egg_particles.retain(|p| p.4 > 0.0);
+    for p in &fx.ai_egg_particles {
+        let color = [0.9, 0.0, 0.0, (p.4 / 0.7).min(1.0) as f32];
+        piston_window::ellipse(color, [p.0-2.0, p.1-2.0, 4.0, 4.0], c.transform, g);
+    }
+    // 顶部UI：关卡/分数/目标
+    // ====== 怪诞哥特风格游戏区UI边框（提前绘制，避免遮挡游戏内容） ======
+    let border_outer = [0.08, 0.08, 0.12, 0.7]; // 加透明度
+    let border_inner = [0.7, 0.0, 0.1, 0.5];
+    let border_highlight = [0.95, 0.95, 0.98, 0.13];
+    let border_x = game_x - 8.0;
+    let border_y = game_y - 8.0;
+    let border_w = 616.0;
+    let border_h = 616.0;
+    // 外黑框（只画边线）
+    rectangle(border_outer, [border_x, border_y, border_w, 8.0], c.transform, g); // 上
+    rectangle(border_outer, [border_x, border_y+border_h-8.0, border_w, 8.0], c.transform, g); // 下
+    rectangle(border_outer, [border_x, border_y, 8.0, border_h], c.transform, g); // 左
+    rectangle(border_outer, [border_x+border_w-8.0, border_y, 8.0, border_h], c.transform, g); // 右
+    // 内血红细线
+    rectangle(border_inner, [game_x-2.0, game_y-2.0, 604.0, 4.0], c.transform, g); // 上
+    rectangle(border_inner, [game_x-2.0, game_y+602.0, 604.0, 4.0], c.transform, g); // 下
+    rectangle(border_inner, [game_x-2.0, game_y-2.0, 4.0, 604.0], c.transform, g); // 左
+    rectangle(border_inner, [game_x+602.0, game_y-2.0, 4.0, 604.0], c.transform, g); // 右
+    // 内高光
+    rectangle(border_highlight, [game_x+4.0, game_y+4.0, 592.0, 2.0], c.transform, g); // 上
+    rectangle(border_highlight, [game_x+4.0, game_y+596.0, 592.0, 2.0], c.transform, g); // 下
+    rectangle(border_highlight, [game_x+4.0, game_y+4.0, 2.0, 592.0], c.transform, g); // 左
+    rectangle(border_highlight, [game_x+596.0, game_y+4.0, 2.0, 592.0], c.transform, g); // 右
+    // 四角哥特装饰（圆+三角）
+    let goth_color = [0.3, 0.0, 0.1, 0.5];
+    let tri = |cx: f64, cy: f64, r: f64, ang: f64| {
+        [
+            [cx + r * (ang).cos(), cy + r * (ang).sin()],
+            [cx + r * (ang+2.3).cos(), cy + r * (ang+2.3).sin()],
+            [cx + r * (ang-2.3).cos(), cy + r * (ang-2.3).sin()],
+        ]
+    };
+    ellipse(goth_color, [border_x-10.0, border_y-10.0, 20.0, 20.0], c.transform, g); // 左上
+    polygon(goth_color, &tri(border_x+10.0, border_y+10.0, 14.0, 2.4), c.transform, g);
+    ellipse(goth_color, [border_x+border_w-10.0, border_y-10.0, 20.0, 20.0], c.transform, g); // 右上
+    polygon(goth_color, &tri(border_x+border_w-10.0, border_y+10.0, 14.0, 0.7), c.transform, g);
+    ellipse(goth_color, [border_x-10.0, border_y+border_h-10.0, 20.0, 20.0], c.transform, g); // 左下
+    polygon(goth_color, &tri(border_x+10.0, border_y+border_h-10.0, 14.0, -2.4), c.transform, g);
+    ellipse(goth_color, [border_x+border_w-10.0, border_y+border_h-10.0, 20.0, 20.0], c.transform, g); // 右下
+    polygon(goth_color, &tri(border_x+border_w-10.0, border_y+border_h-10.0, 14.0, -0.7), c.transform, g);
+    // 内侧裂纹/滴血
+    for i in 0..8 {
+        let fx = game_x + 20.0 + i as f64 * 70.0 + (bg_time*2.0+i as f64).sin()*2.0;
+        let fy = game_y + 4.0 + (bg_time*1.7+i as f64).cos()*4.0;
+        line([0.5,0.0,0.0,0.5], 2.0, [fx, fy, fx+6.0, fy+18.0], c.transform, g);
+        ellipse([0.7,0.0,0.0,0.5], [fx+3.0, fy+18.0, 5.0, 7.0], c.transform, g);
+    }
+    // ====== 恐怖梦核风格UI ======
+    let t = bg_time;
+    // 双人对战没有关卡目标，改为下面两位玩家各自的面板
+    let goal_text = match game.get_versus() {
+        Some(_) => String::new(),
+        None => format!("第{}关 目标分数：{}/{}  总分：{}", game.level, game.level_score, game.get_level_goal(), game.get_score()),
+    };
+    let tip_text = match playback {
+        Some(player) => format!(
+            "回放 x{}{}  空格暂停 F快进 N逐帧",
+            player.speed(),
+            if player.is_paused() { " 已暂停" } else { "" }
+        ),
+        None => {
+            let (pause, restart) = (bindings.hint(Action::Pause), bindings.hint(Action::Restart));
+            match bot {
+                Some(bot) => format!("机器人对局  {}暂停  {}重开  超时 {}/{}", pause, restart, bot.timeouts(), bot.moves()),
+                None if game.get_versus().is_some() => format!("{}暂停  {}重开  玩家一方向键  玩家二WASD", pause, restart),
+                None => format!("{}暂停  {}重开  方向键移动", pause, restart),
+            }
+        }
+    };
+    let goal_x = 60.0;
+    let goal_y = 60.0;
+    let tip_x = 180.0;
+    let tip_y = 780.0;
+    let size_goal = 36;
+    let size_tip = 26;
+    // 动态参数
+    let shake_x = (t*2.1).sin()*2.0;
+    let shake_y = (t*1.7).cos()*2.0;
+    let scale = 1.0 + (t*0.9).sin()*0.03;
+    let main_color = [0.8,0.7,1.0,1.0];
+    let glow_color = [0.9, 0.2, 0.8, (0.5 + 0.3*(t*1.3).sin().abs()) as f32];
+    let shadow_color = [0.0,0.0,0.0,0.5];
+    let remnant_color = [0.2,0.2,0.3,0.4];
+    // 残影
+    piston_window::text(remnant_color, size_goal, &goal_text, glyphs, c.transform.trans(goal_x+3.0, goal_y+3.0).scale(scale,scale), g).ok();
+    piston_window::text(remnant_color, size_tip, &tip_text, glyphs, c.transform.trans(tip_x+3.0, tip_y+3.0).scale(scale,scale), g).ok();
+    // 发光
+    piston_window::text(glow_color, size_goal, &goal_text, glyphs, c.transform.trans(goal_x, goal_y).scale(scale,scale), g).ok();
+    piston_window::text(glow_color, size_tip, &tip_text, glyphs, c.transform.trans(tip_x, tip_y).scale(scale,scale), g).ok();
+    // 主体
+    piston_window::text(main_color, size_goal, &goal_text, glyphs, c.transform.trans(goal_x+shake_x, goal_y+shake_y).scale(scale,scale), g).unwrap();
+    piston_window::text(main_color, size_tip, &tip_text, glyphs, c.transform.trans(tip_x+shake_x, tip_y+shake_y).scale(scale,scale), g).unwrap();
+    // 阴影
+    piston_window::text(shadow_color, size_goal, &goal_text, glyphs, c.transform.trans(goal_x, goal_y+2.0), g).ok();
+    piston_window::text(shadow_color, size_tip, &tip_text, glyphs, c.transform.trans(tip_x, tip_y+2.0), g).ok();
+    // ====== 双人对战面板 ======
+    if let Some(versus) = game.get_versus() {
+        let target = match versus.mode() {
+            VersusMode::FirstTo { food } => format!("/{}", food),
+            VersusMode::LastStanding => String::new(),
+        };
+        let panels = [
+            ("玩家一 方向键", game.get_score(), [0.7, 0.8, 1.0, 1.0], game_x),
+            ("玩家二 WASD", game.get_players()[0].score(), [1.0, 0.8, 0.85, 1.0], game_x + 310.0),
+        ];
+        for (i, (name, score, color, panel_x)) in panels.into_iter().enumerate() {
+            let panel_y = 18.0 + (t*1.3 + i as f64).sin()*2.0;
+            rectangle([0.05, 0.0, 0.1, 0.6], [panel_x, panel_y, 290.0, 70.0], c.transform, g);
+            rectangle([color[0], color[1], color[2], 0.25], [panel_x, panel_y + 66.0, 290.0, 4.0], c.transform, g);
+            piston_window::text(color, 22, name, glyphs, c.transform.trans(panel_x + 12.0, panel_y + 28.0), g).ok();
+            let score_text = format!("分数：{}{}", score, target);
+            piston_window::text(glow_color, 26, &score_text, glyphs, c.transform.trans(panel_x + 12.0 + shake_x, panel_y + 60.0), g).ok();
+        }
+    }
+    // ====== UI旁梦核符号 ======
+    let symbol_pool = ["?", "!", "鬼", "ERROR", "EXIT", "门", "眼"];
+    for i in 0..2 {
+        let idx = ((t*0.7+i as f64*1.3).sin().abs() * (symbol_pool.len() as f64)).floor() as usize % symbol_pool.len();
+        let ch = symbol_pool[idx];
+        let sx = goal_x + 320.0 + (i as f64)*60.0 + (t*1.2+i as f64).sin()*18.0;
+        let sy = goal_y + 8.0 + (t*1.5+i as f64).cos()*12.0;
+        let alpha = 0.18 + 0.18*(t*1.7+i as f64).sin().abs() as f32;
+        let color = [0.8,0.2,0.8,alpha];
+        piston_window::text(color, 28, ch, glyphs, c.transform.trans(sx, sy), g).ok();
+    }
+    // ====== UI下方漂浮雾气 ======
+    for i in 0..2 {
+        let mx = goal_x + 180.0 + (t*0.8+i as f64*1.7).sin()*60.0;
+        let my = goal_y + 38.0 + (t*1.1+i as f64*1.3).cos()*10.0;
+        let rx = 80.0 + (t*1.2+i as f64*1.5).sin()*18.0;
+        let ry = 22.0 + (t*1.3+i as f64*1.2).cos()*6.0;
+        let alpha = 0.10 + 0.10*(t*1.5+i as f64).sin().abs() as f32;
+        let color = [0.7,0.2,0.8,alpha];
+        ellipse(color, [mx-rx/2.0, my-ry/2.0, rx, ry], c.transform, g);
+    }
+    // 侧边偶尔闪现恐怖符号
+    if (bg_time * 1.5).sin() > 0.92 {
+        let transform_side = c.transform.trans(20.0, 400.0).rot_rad(-0.4).scale(1.8, 1.8);
+        piston_window::text([0.8, 0.0, 0.0, 0.18], 32, "手", glyphs, transform_side, g).ok();
+    }
+    if (bg_time * 1.2).cos() > 0.93 {
+        let transform_side = c.transform.trans(620.0, 700.0).rot_rad(0.3).scale(1.5, 1.5);
+        piston_window::text([0.9, 0.0, 0.0, 0.13], 32, "鬼", glyphs, transform_side, g).ok();
+    }
+    // ====== 梦核/怪核符号随机浮现与闪现 ======
+    // 生成新符号
+    if settings.effects > 0.0 && bg_time - fx.last_weirdcore_time > 1.5 + (bg_time * 0.7).sin().abs() * 1.2 {
+        let mut rng = rand::thread_rng();
+        let n = rng.gen_range(1..=2);
+        for _ in 0..n {
+            let (ch, color) = WEIRDCORE_SYMBOL_POOL[rng.gen_range(0..WEIRDCORE_SYMBOL_POOL.len())];
+            let x = rng.gen_range(game_x + 40.0..game_x + 560.0);
+            let y = rng.gen_range(game_y + 40.0..game_y + 560.0);
+            let angle = rng.gen_range(-0.5..0.5);
+            let scale = rng.gen_range(0.9..1.4);
+            let max_life = rng.gen_range(0.18..0.38);
+            fx.weirdcore_symbols.push(WeirdcoreSymbol {
+                ch,
+                x,
+                y,
+                angle,
+                scale,
+                alpha: 0.0,
+                life: max_life,
+                max_life,
+                color,
+            });
+        }
+        fx.last_weirdcore_time = bg_time;
+    }
+    // 更新并绘制符号
+    let mut i = 0;
+    while i < fx.weirdcore_symbols.len() {
+        let s = &mut fx.weirdcore_symbols[i];
+        let t = 1.0 - (s.life / s.max_life) as f32;
+        // 透明度渐入渐出
+        if t < 0.2 {
+            s.alpha = t / 0.2;
+        } else if t > 0.8 {
+            s.alpha = (1.0 - t) / 0.2;
+        } else {
+            s.alpha = 1.0;
+        }
+        // 抖动/缩放/旋转
+        let scale = s.scale * (1.0 + 0.08 * (bg_time * 7.0 + i as f64).sin());
+        let angle = s.angle + (bg_time * 2.0 + i as f64).cos() * 0.08;
+        let color = [s.color[0], s.color[1], s.color[2], s.color[3] * s.alpha * settings.effects as f32];
+        let transform = c.transform.trans(s.x, s.y).rot_rad(angle).scale(scale, scale);
+        let font_size = if s.ch.len() > 2 { 28 } else { 38 };
+        piston_window::text(color, font_size, s.ch, glyphs, transform, g).ok();
+        s.life -= 0.016;
+        if s.life <= 0.0 {
+            fx.weirdcore_symbols.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// 绘制进入游戏时的过渡动画，`t` 从 0 到 1
+pub fn draw_intro(app: &mut App, t: f64, c: Context, g: &mut G2d) {
+    let (window_width, window_height) = (app.window_width, app.window_height);
+    let (game_x, game_y, bg_time) = (app.game_x, app.game_y, app.bg_time);
+    let (game, settings) = (&app.game, &app.settings);
+    let glyphs = &mut app.glyphs;
+    let fx = settings.effects;
+    let shake = (1.0-t) * 8.0 * (bg_time*7.0).sin() * fx;
+    let scale = (1.0 + (1.0-t) * 0.08 * (bg_time*2.0).sin() * fx) * board_scale(game);
+    let rot = (1.0-t) * 0.08 * (bg_time*1.3).cos() * fx;
+    let c_game = &c.trans(game_x+shake, game_y-shake).rot_rad(rot).scale(scale, scale);
+    draw_game(game, c_game, g, bg_time, glyphs);
+    // 2. 叠加全屏渐变色块
+    let fade = t;
+    let color = [
+        1.0 * fade as f32 + 0.7 * (1.0-fade) as f32,
+        0.2 * fade as f32 + 0.0 * (1.0-fade) as f32,
+        0.3 * fade as f32 + 0.1 * (1.0-fade) as f32,
+        (0.0 + 0.85 * fade) as f32
+    ];
+    rectangle(color, [0.0, 0.0, window_width as f64, window_height as f64], c.transform, g);
+    // 3. 符号闪现
+    let symbol_pool = ["?", "!", "鬼", "ERROR", "EXIT", "门", "眼"];
+    for i in 0..3 {
+        let idx = ((bg_time*0.7+i as f64*1.3).sin().abs() * (symbol_pool.len() as f64)).floor() as usize % symbol_pool.len();
+        let ch = symbol_pool[idx];
+        let sx = (window_width as f64)/2.0 + (i as f64-1.0)*120.0 + (bg_time*1.2+i as f64).sin()*30.0;
+        let sy = (window_height as f64)/2.0 + (bg_time*1.5+i as f64).cos()*18.0;
+        let alpha = 0.18 + 0.38*(1.0-t) as f32 * ((bg_time*2.0+i as f64).sin().abs() as f32);
+        let color = [0.9,0.2,0.8,alpha];
+        piston_window::text(color, 54, ch, glyphs, c.transform.trans(sx, sy), g).ok();
+    }
+}
+
+/// 绘制结束这一局时溅出的血红色粒子和游戏区闪光
+pub fn draw_death(app: &App, c: Context, g: &mut G2d) {
+    let (game_x, game_y) = (app.game_x, app.game_y);
+    let (settings, fx) = (&app.settings, &app.fx);
+    // 居中粒子特效
+    for p in &fx.particles {
+        let px = p.x + game_x;
+        let py = p.y + game_y;
+        ellipse([0.8, 0.0, 0.0, (p.life / 1.2).min(1.0) as f32], [px, py, 6.0, 6.0], c.transform, g);
+    }
+    // 居中闪光
+    let flash_alpha = (fx.flash_timer * 20.0).sin().abs().min(1.0) * 0.5 * settings.effects;
+    if flash_alpha > 0.01 {
+        rectangle([1.0, 1.0, 1.0, flash_alpha as f32], [game_x, game_y, 600.0, 600.0], c.transform, g);
+    }
+}
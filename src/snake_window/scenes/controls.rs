@@ -0,0 +1,109 @@
+use crate::snake_window::app::{report_conflicts, App, BINDINGS_PATH};
+use crate::snake_window::bindings::Action;
+use crate::snake_window::scenes::{Scene, Transition};
+use piston_window::{rectangle, Button, Context, Event, G2d, Key, PressEvent, Transformed};
+use std::path::Path;
+
+/// 按键设置界面正在等待的新按键：替换原有按键，或追加一个
+#[derive(Clone, Copy)]
+enum Capture {
+    Replace,
+    Add,
+}
+
+/// 按键设置界面：每个动作一行，冲突的按键标红，返回时保存
+pub struct Controls {
+    selected: usize,
+    capture: Option<Capture>,
+}
+
+impl Controls {
+    pub fn new() -> Controls {
+        Controls { selected: 0, capture: None }
+    }
+}
+
+impl Default for Controls {
+    fn default() -> Controls {
+        Controls::new()
+    }
+}
+
+impl Scene for Controls {
+    fn exit(&mut self, app: &mut App) {
+        report_conflicts(&app.bindings);
+        if let Err(e) = app.bindings.save(Path::new(BINDINGS_PATH)) {
+            eprintln!("保存按键配置失败: {}", e);
+        }
+    }
+
+    fn update(&mut self, app: &mut App, dt: f64) -> Transition {
+        app.bg_time += dt;
+        Transition::None
+    }
+
+    fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d) {
+        let (window_width, window_height, bg_time) = (app.window_width, app.window_height, app.bg_time);
+        let (selected, capture) = (self.selected, self.capture);
+        let bindings = &app.bindings;
+        let glyphs = &mut app.glyphs;
+        // 暗红背景
+        let t = (bg_time * 0.5).sin() * 0.5 + 0.5;
+        rectangle([(0.08 + 0.1 * t) as f32, 0.0, 0.06, 1.0], [0.0, 0.0, window_width as f64, window_height as f64], c.transform, g);
+        let title = "按键设置";
+        let shake_x = (bg_time * 2.1).sin() * 3.0;
+        piston_window::text([0.7, 0.0, 0.0, 0.4], 56, title, glyphs, c.transform.trans(233.0 + shake_x, 113.0), g).ok();
+        piston_window::text([0.95, 0.1, 0.1, 1.0], 56, title, glyphs, c.transform.trans(230.0 + shake_x, 110.0), g).ok();
+        // 每个动作一行，冲突的按键标红
+        for (i, &action) in Action::ALL.iter().enumerate() {
+            let y = 170.0 + i as f64 * 30.0;
+            if i == selected {
+                rectangle([0.6, 0.0, 0.2, 0.35], [60.0, y - 22.0, 580.0, 28.0], c.transform, g);
+            }
+            let conflicting = bindings.is_conflicting(action);
+            let color = if conflicting { [1.0, 0.3, 0.3, 1.0] } else { [0.95, 0.9, 0.9, 1.0] };
+            piston_window::text(color, 20, action.label(), glyphs, c.transform.trans(80.0, y), g).ok();
+            let keys = match capture {
+                Some(_) if i == selected => "请按下新按键…".to_string(),
+                _ if conflicting => format!("{}  冲突", bindings.describe(action)),
+                _ => bindings.describe(action),
+            };
+            piston_window::text(color, 20, &keys, glyphs, c.transform.trans(300.0, y), g).ok();
+        }
+        if let Some(conflict) = bindings.conflicts().first() {
+            let names: Vec<&str> = conflict.actions.iter().map(|a| a.label()).collect();
+            let text = format!("{:?} 同时绑定了 {}，只会触发{}", conflict.key, names.join("、"), names[0]);
+            piston_window::text([1.0, 0.3, 0.3, 1.0], 18, &text, glyphs, c.transform.trans(80.0, 630.0), g).ok();
+        }
+        let tip = "↑↓选择  回车改键  Tab追加按键  退格恢复默认  ESC保存返回";
+        let flash_alpha = 0.5 + 0.5 * (bg_time * 2.2).sin().abs();
+        piston_window::text([1.0, 0.2, 0.2, flash_alpha as f32], 20, tip, glyphs, c.transform.trans(80.0, 700.0), g).ok();
+    }
+
+    fn handle_input(&mut self, app: &mut App, event: &Event) -> Transition {
+        let Some(Button::Keyboard(key)) = event.press_args() else {
+            return Transition::None;
+        };
+        let action = Action::ALL[self.selected];
+        // 等待新按键时，任何键（包括退出键）都绑定到选中的动作上
+        if let Some(mode) = self.capture.take() {
+            match mode {
+                Capture::Replace => app.bindings.bind(action, key),
+                Capture::Add => app.bindings.add(action, key),
+            }
+            return Transition::None;
+        }
+        let count = Action::ALL.len();
+        match key {
+            Key::Up => self.selected = (self.selected + count - 1) % count,
+            Key::Down => self.selected = (self.selected + 1) % count,
+            Key::Return => self.capture = Some(Capture::Replace),
+            Key::Tab => self.capture = Some(Capture::Add),
+            Key::Backspace => app.bindings.reset(action),
+            // 退出键在这里只用来返回
+            _ if key == Key::Escape || app.is_quit(key) => return Transition::Pop,
+            _ => {}
+        }
+        Transition::None
+    }
+}
@@ -0,0 +1,158 @@
+use crate::snake_game::game::Winner;
+use crate::snake_game::highscore::HighScoreEntry;
+use crate::snake_window::app::{save_replay, save_run, App, HIGH_SCORES_PATH, MAX_NAME_LEN};
+use crate::snake_window::bindings::Action;
+use crate::snake_window::input::handle_replay_key;
+use crate::snake_window::scenes::board::draw_death;
+use crate::snake_window::scenes::{Scene, Transition};
+use piston_window::{rectangle, Button, Context, Event, G2d, Key, PressEvent, TextEvent, Transformed};
+use std::path::Path;
+
+/// 游戏结束：先播放死亡回放，再盖上结果；上榜时输入名字，重开后关闭
+pub struct GameOver {
+    /// 上榜后正在输入的名字
+    name_entry: Option<String>,
+}
+
+impl GameOver {
+    pub fn new() -> GameOver {
+        GameOver { name_entry: None }
+    }
+}
+
+impl Default for GameOver {
+    fn default() -> GameOver {
+        GameOver::new()
+    }
+}
+
+impl Scene for GameOver {
+    fn enter(&mut self, app: &mut App) {
+        // 填满梦境是胜利，不回放
+        if !app.game.is_board_full() {
+            app.killcam_playback = app.killcam.start(&app.game);
+        }
+        if app.playback.is_none() {
+            save_replay(&app.game);
+        }
+        if app.records_run {
+            save_run(&app.game);
+            // 双人对战不上排行榜
+            if app.game.get_versus().is_none() && app.high_scores.qualifies(app.game.get_score()) {
+                self.name_entry = Some(String::new());
+            }
+        }
+    }
+
+    fn update(&mut self, app: &mut App, dt: f64) -> Transition {
+        app.advance(dt);
+        if app.game.is_game_over() {
+            Transition::None
+        } else {
+            Transition::Pop
+        }
+    }
+
+    fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d) {
+        if app.killcam_playback.is_some() {
+            return;
+        }
+        let (window_width, window_height) = (app.window_width, app.window_height);
+        // 半透明黑色遮罩
+        rectangle([0.0, 0.0, 0.0, 0.6], [0.0, 0.0, window_width as f64, window_height as f64], c.transform, g);
+        draw_death(app, c, g);
+        let (game, bindings) = (&app.game, &app.bindings);
+        let glyphs = &mut app.glyphs;
+        // 大字“游戏结束”，填满游戏区获胜时换成胜利文字，双人对战时宣布胜者
+        let over_text = match game.get_versus().and_then(|v| v.winner()) {
+            Some(Winner::PlayerOne) => "玩家一获胜",
+            Some(Winner::PlayerTwo) => "玩家二获胜",
+            Some(Winner::Draw) => "同归于尽",
+            None if game.is_board_full() => "梦境已满",
+            None => "游戏结束",
+        };
+        let over_size = 56;
+        let over_w = over_text.chars().count() as f64 * over_size as f64 * 0.9;
+        let over_x = (window_width as f64 - over_w) / 2.0 - 70.0;
+        let transform_over_shadow = c.transform.trans(over_x + 4.0, 340.0);
+        let transform_over = c.transform.trans(over_x, 336.0);
+        piston_window::text([0.0, 0.0, 0.0, 0.7], over_size, over_text, glyphs, transform_over_shadow, g).ok();
+        piston_window::text([1.0, 0.2, 0.2, 1.0], over_size, over_text, glyphs, transform_over, g).unwrap();
+        // 分数和关卡
+        let result_text = match game.get_versus() {
+            Some(_) => format!("玩家一: {}   玩家二: {}   种子: {}", game.get_score(), game.get_players()[0].score(), game.get_seed()),
+            None => format!("分数: {}   关卡: {}   种子: {}", game.get_score(), game.get_level(), game.get_seed()),
+        };
+        let result_size = 32;
+        let result_w = result_text.chars().count() as f64 * result_size as f64 * 0.6;
+        let result_x = (window_width as f64 - result_w) / 2.0 - 40.0;
+        let transform_result = c.transform.trans(result_x, 400.0);
+        piston_window::text([1.0, 1.0, 1.0, 1.0], result_size, &result_text, glyphs, transform_result, g).unwrap();
+        // 单人游戏显示死因
+        let single = game.get_versus().is_none() && !game.is_board_full();
+        if let Some(cause) = game.get_death_cause().filter(|_| single) {
+            let cause_text = format!("死因：{}", cause.description());
+            let cause_size = 22;
+            let cause_w = cause_text.chars().count() as f64 * cause_size as f64 * 0.6;
+            let cause_x = (window_width as f64 - cause_w) / 2.0 - 40.0;
+            piston_window::text([0.9, 0.5, 0.5, 1.0], cause_size, &cause_text, glyphs, c.transform.trans(cause_x, 432.0), g).ok();
+        }
+        // 重开提示，上榜时提示输入名字
+        let tip_text = match &self.name_entry {
+            Some(name) => format!("新纪录！输入名字：{}_  回车确认", name),
+            None if game.is_board_full() => format!("你填满了整个梦境  按{}键重新开始", bindings.hint(Action::Restart)),
+            None => format!("按{}键重新开始", bindings.hint(Action::Restart)),
+        };
+        let tip_size = 24;
+        let tip_w = tip_text.chars().count() as f64 * tip_size as f64 * 0.6;
+        let tip_x = (window_width as f64 - tip_w) / 2.0 - 40.0;
+        let transform_tip = c.transform.trans(tip_x, 460.0);
+        piston_window::text([1.0, 1.0, 0.2, 1.0], tip_size, &tip_text, glyphs, transform_tip, g).unwrap();
+    }
+
+    fn handle_input(&mut self, app: &mut App, event: &Event) -> Transition {
+        if let Some(Button::Keyboard(key)) = event.press_args() {
+            if app.is_quit(key) {
+                return Transition::Quit;
+            }
+        }
+        if app.killcam_playback.is_some() {
+            // 死亡回放中按任意键跳过
+            if let Some(Button::Keyboard(_)) = event.press_args() {
+                app.killcam_playback = None;
+            }
+        } else if let Some(name) = &mut self.name_entry {
+            // 上榜后输入名字，此时按键不传给游戏
+            if let Some(text) = event.text_args() {
+                for ch in text.chars().filter(|ch| !ch.is_control()) {
+                    if name.chars().count() < MAX_NAME_LEN {
+                        name.push(ch);
+                    }
+                }
+            }
+            if let Some(Button::Keyboard(key)) = event.press_args() {
+                if key == Key::Backspace {
+                    name.pop();
+                } else if key == Key::Return {
+                    let name = name.trim().to_string();
+                    let name = if name.is_empty() { "无名".to_string() } else { name };
+                    app.high_scores.insert(HighScoreEntry::from_game(&app.game, name));
+                    if let Err(e) = app.high_scores.save(Path::new(HIGH_SCORES_PATH)) {
+                        eprintln!("保存排行榜失败: {}", e);
+                    }
+                    self.name_entry = None;
+                }
+            }
+        } else if let Some(Button::Keyboard(key)) = event.press_args() {
+            match &mut app.playback {
+                Some(player) => handle_replay_key(&app.bindings, player, key),
+                None => app.queue_key(key),
+            }
+        }
+        Transition::None
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,54 @@
+use crate::snake_game::highscore::format_date;
+use crate::snake_window::app::App;
+use crate::snake_window::scenes::{Scene, Transition};
+use piston_window::{rectangle, Button, Context, Event, G2d, PressEvent, Transformed};
+
+/// 排行榜界面，按任意键返回
+pub struct Leaderboard;
+
+impl Scene for Leaderboard {
+    fn update(&mut self, app: &mut App, dt: f64) -> Transition {
+        app.bg_time += dt;
+        Transition::None
+    }
+
+    fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d) {
+        let (window_width, window_height, bg_time) = (app.window_width, app.window_height, app.bg_time);
+        let high_scores = &app.high_scores;
+        let glyphs = &mut app.glyphs;
+        // 暗红背景
+        let t = (bg_time * 0.5).sin() * 0.5 + 0.5;
+        rectangle([(0.08 + 0.1 * t) as f32, 0.0, 0.06, 1.0], [0.0, 0.0, window_width as f64, window_height as f64], c.transform, g);
+        // 标题
+        let title = "噩梦排行榜";
+        let shake_x = (bg_time * 2.1).sin() * 3.0;
+        piston_window::text([0.7, 0.0, 0.0, 0.4], 56, title, glyphs, c.transform.trans(203.0 + shake_x, 123.0), g).ok();
+        piston_window::text([0.95, 0.1, 0.1, 1.0], 56, title, glyphs, c.transform.trans(200.0 + shake_x, 120.0), g).ok();
+        // 表头与记录
+        let header = "名次  名字          分数  关卡  长度  时长    日期        种子";
+        piston_window::text([0.9, 0.7, 1.0, 0.8], 16, header, glyphs, c.transform.trans(40.0, 190.0), g).ok();
+        if high_scores.entries().is_empty() {
+            piston_window::text([0.8, 0.8, 0.8, 0.7], 28, "还没有人从噩梦中留下名字", glyphs, c.transform.trans(150.0, 300.0), g).ok();
+        }
+        for (i, e) in high_scores.entries().iter().enumerate() {
+            let row = format!(
+                "{:>2}.   {:<12}  {:>4}  {:>4}  {:>4}  {:>4}秒  {}  {}",
+                i + 1, e.name, e.score, e.level, e.length, e.time_played as u64, format_date(e.date), e.seed
+            );
+            let alpha = 1.0 - i as f32 * 0.06;
+            let color = if i == 0 { [1.0, 0.85, 0.2, alpha] } else { [0.95, 0.9, 0.9, alpha] };
+            piston_window::text(color, 16, &row, glyphs, c.transform.trans(40.0, 230.0 + i as f64 * 40.0), g).ok();
+        }
+        let tip = "按任意键返回";
+        let flash_alpha = 0.5 + 0.5 * (bg_time * 2.2).sin().abs();
+        piston_window::text([1.0, 0.2, 0.2, flash_alpha as f32], 28, tip, glyphs, c.transform.trans(260.0, 700.0), g).ok();
+    }
+
+    fn handle_input(&mut self, app: &mut App, event: &Event) -> Transition {
+        match event.press_args() {
+            Some(Button::Keyboard(key)) if app.is_quit(key) => Transition::Quit,
+            Some(Button::Keyboard(_)) => Transition::Pop,
+            _ => Transition::None,
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::snake_game::game::Input;
+use crate::snake_window::app::App;
+use crate::snake_window::input::{handle_replay_key, key_to_input};
+use crate::snake_window::scenes::{Scene, Transition};
+use piston_window::{rectangle, Button, Context, Event, G2d, PressEvent, Transformed};
+
+/// 过关界面：按任意键进入下一关，回放时由记录的输入决定
+pub struct LevelComplete;
+
+impl Scene for LevelComplete {
+    fn update(&mut self, app: &mut App, dt: f64) -> Transition {
+        if let Some(player) = &mut app.playback {
+            player.advance(&mut app.game, dt);
+        }
+        if app.game.waiting_next_level {
+            Transition::None
+        } else {
+            Transition::Pop
+        }
+    }
+
+    fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d) {
+        let (window_width, bg_time) = (app.window_width, app.bg_time);
+        let game = &app.game;
+        let glyphs = &mut app.glyphs;
+            // 恐怖背景（递增）
+            let t = (bg_time * 0.1).sin() * 0.5 + 0.5;
+            let level = game.level;
+            let base = 0.2 + 0.1 * (level as f32).min(5.0);
+            let red = base + 0.2 * t as f32 + 0.08 * (level as f32);
+            let green = base * (1.0 - 0.08 * (level as f32));
+            let blue = base * (1.0 - 0.12 * (level as f32));
+                for i in 0..40 {
+                let k = i as f32 / 39.0;
+                let color = [
+                    red * (1.0 - k) + blue * k,
+                    green * (1.0 - k) + red * k,
+                    blue * (1.0 - k) + red * k,
+                    1.0,
+                ];
+                rectangle(color, [0.0, i as f64 * 20.0, window_width as f64, 20.0], c.transform, g);
+            }
+            // 恐怖关卡切换界面
+            let over_text = format!("第{}关完成", game.level);
+            let tip_text = "按任意键进入下一关";
+            let transform_over = c.transform.trans(210.0, 400.0);
+            let transform_tip = c.transform.trans(220.0, 480.0);
+            piston_window::text([1.0, 0.2, 0.2, 1.0], 56, &over_text, glyphs, transform_over, g).unwrap();
+            piston_window::text([1.0, 1.0, 0.2, 1.0], 28, tip_text, glyphs, transform_tip, g).unwrap();
+    }
+
+    fn handle_input(&mut self, app: &mut App, event: &Event) -> Transition {
+        let Some(Button::Keyboard(key)) = event.press_args() else {
+            return Transition::None;
+        };
+        if app.is_quit(key) {
+            return Transition::Quit;
+        }
+        match &mut app.playback {
+            Some(player) => handle_replay_key(&app.bindings, player, key),
+            None => {
+                // 方向键同时排进转向队列
+                app.game.handle_input(Input::NextLevel);
+                match key_to_input(&app.bindings, key) {
+                    Some(Input::Turn(_)) if app.bot.is_some() => {}
+                    Some(input @ (Input::Turn(_) | Input::Turn2(_))) => app.game.handle_input(input),
+                    _ => {}
+                }
+            }
+        }
+        Transition::None
+    }
+}
@@ -0,0 +1,147 @@
+use crate::snake_window::app::App;
+use piston_window::{Context, Event, G2d};
+
+pub mod board;
+pub mod controls;
+pub mod game_over;
+pub mod leaderboard;
+pub mod level_complete;
+pub mod options;
+pub mod paused;
+pub mod playing;
+pub mod replay;
+pub mod start;
+
+/// 界面处理完输入或更新后要做的切换
+pub enum Transition {
+    /// 留在当前界面
+    None,
+    /// 在当前界面上打开一个新界面
+    Push(Box<dyn Scene>),
+    /// 关闭当前界面，回到下面的界面
+    Pop,
+    /// 用新界面替换当前界面
+    Replace(Box<dyn Scene>),
+    /// 关闭上面的全部界面，回到最下面的开始界面
+    PopToRoot,
+    /// 退出游戏
+    Quit,
+}
+
+/// 一个界面：开始界面、游戏中、暂停、过关、游戏结束等
+///
+/// 界面之间共享的状态（当前这一局、设置、排行榜等）都在 `App` 里，界面只保存自己的状态。
+pub trait Scene {
+    /// 界面被打开时调用
+    fn enter(&mut self, _app: &mut App) {}
+
+    /// 界面被关闭时调用，包括退出游戏时
+    fn exit(&mut self, _app: &mut App) {}
+
+    /// 上面的界面关闭、重新回到这个界面时调用
+    fn resume(&mut self, _app: &mut App) {}
+
+    /// 按真实时间推进
+    fn update(&mut self, app: &mut App, dt: f64) -> Transition;
+
+    /// 绘制界面
+    fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d);
+
+    /// 处理按键、文字输入和窗口焦点等事件
+    fn handle_input(&mut self, app: &mut App, event: &Event) -> Transition;
+
+    /// 覆盖层只画自己的部分，下面的界面照常绘制
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+/// 界面栈：只有最上面的界面接收输入和更新，绘制从最上面一个非覆盖层的界面开始
+#[derive(Default)]
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    pub fn new() -> SceneStack {
+        SceneStack::default()
+    }
+
+    /// 打开一个界面
+    pub fn push(&mut self, app: &mut App, mut scene: Box<dyn Scene>) {
+        scene.enter(app);
+        self.scenes.push(scene);
+    }
+
+    /// 关闭最上面的界面，回到下面的界面
+    pub fn pop(&mut self, app: &mut App) {
+        self.remove_top(app);
+        self.resume_top(app);
+    }
+
+    /// 从上到下关闭全部界面
+    pub fn clear(&mut self, app: &mut App) {
+        while !self.scenes.is_empty() {
+            self.remove_top(app);
+        }
+    }
+
+    fn remove_top(&mut self, app: &mut App) {
+        if let Some(mut scene) = self.scenes.pop() {
+            scene.exit(app);
+        }
+    }
+
+    fn resume_top(&mut self, app: &mut App) {
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.resume(app);
+        }
+    }
+
+    /// 执行切换，返回游戏是否继续运行
+    pub fn apply(&mut self, app: &mut App, transition: Transition) -> bool {
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => self.push(app, scene),
+            Transition::Pop => self.pop(app),
+            Transition::Replace(scene) => {
+                self.remove_top(app);
+                self.push(app, scene);
+            }
+            Transition::PopToRoot => {
+                while self.scenes.len() > 1 {
+                    self.remove_top(app);
+                }
+                self.resume_top(app);
+            }
+            Transition::Quit => return false,
+        }
+        !self.scenes.is_empty()
+    }
+
+    /// 推进最上面的界面，返回游戏是否继续运行
+    pub fn update(&mut self, app: &mut App, dt: f64) -> bool {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.update(app, dt),
+            None => return false,
+        };
+        self.apply(app, transition)
+    }
+
+    /// 把事件交给最上面的界面，返回游戏是否继续运行
+    pub fn handle_input(&mut self, app: &mut App, event: &Event) -> bool {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.handle_input(app, event),
+            None => return false,
+        };
+        self.apply(app, transition)
+    }
+
+    /// 从最上面一个非覆盖层的界面开始往上绘制
+    pub fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d) {
+        let base = self.scenes.iter().rposition(|s| !s.is_overlay()).unwrap_or(0);
+        for scene in &mut self.scenes[base..] {
+            scene.draw(app, c, g);
+        }
+    }
+}
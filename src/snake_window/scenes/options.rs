@@ -0,0 +1,144 @@
+use crate::snake_game::settings::{Difficulty, Settings, GRID_RANGE, MAX_AI_SNAKES, MAX_VOLUME, SPEED_RANGE};
+use crate::snake_window::app::{App, SETTINGS_PATH};
+use crate::snake_window::scenes::{Scene, Transition};
+use piston_window::{rectangle, Button, Context, Event, G2d, Key, PressEvent, Transformed};
+use std::path::Path;
+
+/// 设置界面的各项
+const OPTION_ROWS: [&str; 7] = ["难度", "模式", "无尽模式游戏区", "速度", "AI蛇数量", "特效强度", "音量"];
+
+/// 设置界面上第 `row` 项当前的值
+fn option_value(settings: &Settings, row: usize) -> String {
+    match row {
+        0 => settings.difficulty.label().to_string(),
+        1 => if settings.endless { "无尽" } else { "战役" }.to_string(),
+        2 => format!("{}×{}", settings.grid_width, settings.grid_height),
+        3 => format!("x{:.2}", settings.speed),
+        4 => settings.ai_snakes.map_or_else(|| "按关卡".to_string(), |n| n.to_string()),
+        5 => format!("{}%", (settings.effects * 100.0).round()),
+        _ => format!("{}%", settings.volume),
+    }
+}
+
+/// 把设置界面上第 `row` 项往左（-1）或往右（1）调一档，不会超出允许的范围
+fn adjust_option(settings: &mut Settings, row: usize, delta: i32) {
+    match row {
+        0 => {
+            let n = Difficulty::ALL.len() as i32;
+            let idx = Difficulty::ALL.iter().position(|&d| d == settings.difficulty).unwrap_or(1) as i32;
+            settings.difficulty = Difficulty::ALL[(idx + delta).rem_euclid(n) as usize];
+        }
+        1 => settings.endless = !settings.endless,
+        2 => {
+            let size = (settings.grid_width + 5 * delta).clamp(GRID_RANGE.0, GRID_RANGE.1);
+            settings.grid_width = size;
+            settings.grid_height = size;
+        }
+        3 => settings.speed = (settings.speed + 0.25 * delta as f64).clamp(SPEED_RANGE.0, SPEED_RANGE.1),
+        4 => {
+            // 顺序为：按关卡、0、1、……
+            let idx = settings.ai_snakes.map_or(0, |n| n as i32 + 1);
+            let idx = (idx + delta).clamp(0, MAX_AI_SNAKES as i32 + 1);
+            settings.ai_snakes = if idx == 0 { None } else { Some(idx as usize - 1) };
+        }
+        5 => settings.effects = ((settings.effects * 10.0).round() as i32 + delta).clamp(0, 10) as f64 / 10.0,
+        _ => settings.volume = (settings.volume as i32 + 10 * delta).clamp(0, MAX_VOLUME as i32) as u32,
+    }
+}
+
+/// 设置界面：←→ 调整选中的一项，返回时保存
+pub struct Options {
+    selected: usize,
+    /// 从开始界面打开时，返回后按新设置重新创建还没开始的这一局
+    rebuild_game: bool,
+}
+
+impl Options {
+    /// 从开始界面打开
+    pub fn new() -> Options {
+        Options { selected: 0, rebuild_game: true }
+    }
+
+    /// 从暂停菜单打开，进行中的这一局不受影响，特效强度立即生效
+    pub fn in_game() -> Options {
+        Options { selected: 0, rebuild_game: false }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options::new()
+    }
+}
+
+impl Scene for Options {
+    fn exit(&mut self, app: &mut App) {
+        if let Err(e) = app.settings.save(Path::new(SETTINGS_PATH)) {
+            eprintln!("保存设置失败: {}", e);
+        }
+        // 还没开始的这一局按新设置重新创建
+        if self.rebuild_game {
+            app.reset_game();
+        }
+    }
+
+    fn update(&mut self, app: &mut App, dt: f64) -> Transition {
+        app.bg_time += dt;
+        Transition::None
+    }
+
+    fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d) {
+        let (window_width, window_height, bg_time) = (app.window_width, app.window_height, app.bg_time);
+        let selected = self.selected;
+        let settings = &app.settings;
+        let glyphs = &mut app.glyphs;
+        // 暗红背景
+        let t = (bg_time * 0.5).sin() * 0.5 + 0.5;
+        rectangle([(0.08 + 0.1 * t) as f32, 0.0, 0.06, 1.0], [0.0, 0.0, window_width as f64, window_height as f64], c.transform, g);
+        let title = "噩梦设置";
+        let shake_x = (bg_time * 2.1).sin() * 3.0 * settings.effects;
+        piston_window::text([0.7, 0.0, 0.0, 0.4], 56, title, glyphs, c.transform.trans(233.0 + shake_x, 143.0), g).ok();
+        piston_window::text([0.95, 0.1, 0.1, 1.0], 56, title, glyphs, c.transform.trans(230.0 + shake_x, 140.0), g).ok();
+        for (i, name) in OPTION_ROWS.iter().enumerate() {
+            let y = 230.0 + i as f64 * 56.0;
+            if i == selected {
+                rectangle([0.6, 0.0, 0.2, 0.35], [80.0, y - 32.0, 540.0, 44.0], c.transform, g);
+            }
+            // 战役模式下游戏区大小不起作用，画暗一些
+            let alpha = if i == 2 && !settings.endless { 0.45 } else { 1.0 };
+            piston_window::text([0.95, 0.9, 0.9, alpha], 28, name, glyphs, c.transform.trans(110.0, y), g).ok();
+            let value = format!("◀ {} ▶", option_value(settings, i));
+            piston_window::text([1.0, 0.85, 0.2, alpha], 28, &value, glyphs, c.transform.trans(380.0, y), g).ok();
+        }
+        let note = "设置在开始新的一局时生效，窗口大小只能在配置文件中修改";
+        piston_window::text([0.9, 0.7, 1.0, 0.7], 18, note, glyphs, c.transform.trans(110.0, 640.0), g).ok();
+        let tip = "↑↓选择  ←→调整  退格恢复默认  ESC保存返回";
+        let flash_alpha = 0.5 + 0.5 * (bg_time * 2.2).sin().abs();
+        piston_window::text([1.0, 0.2, 0.2, flash_alpha as f32], 22, tip, glyphs, c.transform.trans(110.0, 700.0), g).ok();
+    }
+
+    fn handle_input(&mut self, app: &mut App, event: &Event) -> Transition {
+        let Some(Button::Keyboard(key)) = event.press_args() else {
+            return Transition::None;
+        };
+        let count = OPTION_ROWS.len();
+        match key {
+            Key::Up => self.selected = (self.selected + count - 1) % count,
+            Key::Down => self.selected = (self.selected + 1) % count,
+            Key::Left => adjust_option(&mut app.settings, self.selected, -1),
+            Key::Right => adjust_option(&mut app.settings, self.selected, 1),
+            Key::Backspace => {
+                // 窗口大小不在界面上，保留原值
+                app.settings = Settings {
+                    window_width: app.settings.window_width,
+                    window_height: app.settings.window_height,
+                    ..Settings::default()
+                };
+            }
+            // 退出键在这里只用来返回
+            _ if key == Key::Escape || app.is_quit(key) => return Transition::Pop,
+            _ => {}
+        }
+        Transition::None
+    }
+}
@@ -0,0 +1,196 @@
+use crate::snake_game::game::Input;
+use crate::snake_window::app::{App, BOARD_PIXELS};
+use crate::snake_window::bindings::Action;
+use crate::snake_window::scenes::options::Options;
+use crate::snake_window::scenes::{Scene, Transition};
+use piston_window::{ellipse, rectangle, Button, Context, Event, G2d, Key, PressEvent, Transformed};
+
+/// 暂停菜单的各项
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MenuItem {
+    Resume,
+    RestartLevel,
+    RestartRun,
+    Options,
+    QuitToTitle,
+}
+
+impl MenuItem {
+    const ALL: [MenuItem; 5] = [
+        MenuItem::Resume,
+        MenuItem::RestartLevel,
+        MenuItem::RestartRun,
+        MenuItem::Options,
+        MenuItem::QuitToTitle,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            MenuItem::Resume => "继续",
+            MenuItem::RestartLevel => "重玩本关",
+            MenuItem::RestartRun => "重新开始",
+            MenuItem::Options => "设置",
+            MenuItem::QuitToTitle => "返回标题",
+        }
+    }
+}
+
+/// 暂停菜单：盖在冻结的游戏区上，这一局恢复后关闭
+///
+/// 暂停时仍然推进模拟（只有 tick 前进），回放和存档才能逐步对上。
+/// 继续和两种重开都作为游戏输入交给 `Game`，会记进回放。
+pub struct Paused {
+    selected: usize,
+    /// 已经选了继续或重开，等这一局恢复后关闭，期间不再接收按键
+    closing: bool,
+}
+
+impl Paused {
+    pub fn new() -> Paused {
+        Paused { selected: 0, closing: false }
+    }
+
+    /// 执行选中的一项
+    fn choose(&mut self, app: &mut App) -> Transition {
+        let input = match MenuItem::ALL[self.selected] {
+            MenuItem::Resume => Input::TogglePause,
+            MenuItem::RestartLevel => Input::RestartLevel,
+            MenuItem::RestartRun => Input::Restart,
+            MenuItem::Options => return Transition::Push(Box::new(Options::in_game())),
+            MenuItem::QuitToTitle => return Transition::PopToRoot,
+        };
+        app.pending_inputs.push(input);
+        self.closing = true;
+        Transition::None
+    }
+}
+
+/// 数字键 1 到 5 直接选中并执行对应的一项
+fn menu_shortcut(key: Key) -> Option<usize> {
+    match key {
+        Key::D1 => Some(0),
+        Key::D2 => Some(1),
+        Key::D3 => Some(2),
+        Key::D4 => Some(3),
+        Key::D5 => Some(4),
+        _ => None,
+    }
+}
+
+impl Default for Paused {
+    fn default() -> Paused {
+        Paused::new()
+    }
+}
+
+impl Scene for Paused {
+    fn update(&mut self, app: &mut App, dt: f64) -> Transition {
+        app.advance(dt);
+        if app.game.is_paused() {
+            Transition::None
+        } else {
+            Transition::Pop
+        }
+    }
+
+    fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d) {
+        let (window_width, window_height) = (app.window_width as f64, app.window_height as f64);
+        let (game_x, game_y, bg_time) = (app.game_x, app.game_y, app.bg_time);
+        let effects = app.settings.effects;
+        let tip = format!("Tab或数字键选择  回车确认  {}继续", app.bindings.hint(Action::Pause));
+        let glyphs = &mut app.glyphs;
+        // 紫黑色薄纱罩住冻结的游戏区
+        rectangle([0.05, 0.0, 0.08, 0.62], [0.0, 0.0, window_width, window_height], c.transform, g);
+        // 录像带扫描线，缓慢往下爬
+        let offset = (bg_time * 12.0) % 6.0;
+        for i in 0..(BOARD_PIXELS / 6.0) as usize {
+            let y = game_y + i as f64 * 6.0 + offset;
+            if y < game_y + BOARD_PIXELS {
+                rectangle([0.6, 0.4, 0.9, 0.05], [game_x, y, BOARD_PIXELS, 1.5], c.transform, g);
+            }
+        }
+        // 标题：梦核式的残影、发光和轻微抖动
+        let title = "梦境暂停";
+        let shake_x = (bg_time * 2.1).sin() * 3.0 * effects;
+        let shake_y = (bg_time * 1.7).cos() * 2.0 * effects;
+        let title_x = window_width / 2.0 - 130.0;
+        let glow_color = [0.9, 0.2, 0.8, (0.4 + 0.3 * (bg_time * 1.3).sin().abs()) as f32];
+        piston_window::text([0.2, 0.2, 0.3, 0.4], 56, title, glyphs, c.transform.trans(title_x + 4.0, 244.0), g).ok();
+        piston_window::text(glow_color, 56, title, glyphs, c.transform.trans(title_x, 240.0), g).ok();
+        piston_window::text([0.8, 0.7, 1.0, 1.0], 56, title, glyphs, c.transform.trans(title_x + shake_x, 240.0 + shake_y), g).ok();
+        // 菜单面板
+        let panel_w = 320.0;
+        let panel_x = (window_width - panel_w) / 2.0;
+        let panel_y = 280.0;
+        let panel_h = MenuItem::ALL.len() as f64 * 56.0 + 24.0;
+        rectangle([0.08, 0.0, 0.12, 0.8], [panel_x, panel_y, panel_w, panel_h], c.transform, g);
+        rectangle([0.7, 0.2, 0.8, 0.35], [panel_x, panel_y, panel_w, 3.0], c.transform, g);
+        rectangle([0.7, 0.2, 0.8, 0.35], [panel_x, panel_y + panel_h - 3.0, panel_w, 3.0], c.transform, g);
+        for (i, item) in MenuItem::ALL.iter().enumerate() {
+            let y = panel_y + 56.0 + i as f64 * 56.0;
+            let selected = i == self.selected;
+            if selected {
+                let pulse = 0.25 + 0.15 * (bg_time * 3.0).sin().abs() as f32;
+                rectangle([0.6, 0.0, 0.2, pulse], [panel_x + 16.0, y - 34.0, panel_w - 32.0, 44.0], c.transform, g);
+                piston_window::text([1.0, 0.85, 0.2, 1.0], 28, "▶", glyphs, c.transform.trans(panel_x + 36.0, y), g).ok();
+            }
+            let color = if selected { [1.0, 0.95, 0.95, 1.0] } else { [0.8, 0.7, 1.0, 0.7] };
+            let jitter = if selected { (bg_time * 7.0).sin() * 1.5 * effects } else { 0.0 };
+            let label = format!("{}. {}", i + 1, item.label());
+            piston_window::text(color, 28, &label, glyphs, c.transform.trans(panel_x + 90.0 + jitter, y), g).ok();
+        }
+        // 面板两侧缓慢漂浮的梦核符号和雾气
+        let symbol_pool = ["?", "眼", "门", "EXIT"];
+        for (i, ch) in symbol_pool.iter().enumerate() {
+            let side = if i % 2 == 0 { panel_x - 90.0 } else { panel_x + panel_w + 40.0 };
+            let sx = side + (bg_time * 0.9 + i as f64).sin() * 12.0;
+            let sy = panel_y + 60.0 + i as f64 * 70.0 + (bg_time * 1.1 + i as f64).cos() * 10.0;
+            let alpha = (0.12 + 0.2 * (bg_time * 1.4 + i as f64).sin().abs()) as f32 * effects as f32;
+            piston_window::text([0.8, 0.2, 0.8, alpha], 30, ch, glyphs, c.transform.trans(sx, sy), g).ok();
+        }
+        let mist_rx = 260.0 + (bg_time * 0.8).sin() * 30.0;
+        ellipse([0.7, 0.2, 0.8, 0.08], [window_width / 2.0 - mist_rx / 2.0, panel_y + panel_h - 10.0, mist_rx, 40.0], c.transform, g);
+        // 操作提示
+        let flash_alpha = 0.5 + 0.5 * (bg_time * 2.2).sin().abs();
+        piston_window::text([1.0, 0.2, 0.2, flash_alpha as f32], 22, &tip, glyphs, c.transform.trans(panel_x + 10.0, panel_y + panel_h + 60.0), g).ok();
+    }
+
+    fn handle_input(&mut self, app: &mut App, event: &Event) -> Transition {
+        let Some(Button::Keyboard(key)) = event.press_args() else {
+            return Transition::None;
+        };
+        // 转向键不用来选菜单，照常排进转向队列，继续后生效
+        if matches!(app.bindings.input(key), Some(Input::Turn(_) | Input::Turn2(_))) {
+            app.queue_key(key);
+            return Transition::None;
+        }
+        if self.closing {
+            return Transition::None;
+        }
+        let count = MenuItem::ALL.len();
+        match key {
+            Key::Return => return self.choose(app),
+            // 暂停键和退出键在这里都是继续
+            _ if matches!(app.bindings.action(key), Some(Action::Pause | Action::Quit)) => {
+                self.selected = 0;
+                return self.choose(app);
+            }
+            // 方向键没有绑定转向时也可以用来选择
+            Key::Up => self.selected = (self.selected + count - 1) % count,
+            Key::Down | Key::Tab => self.selected = (self.selected + 1) % count,
+            _ => match menu_shortcut(key) {
+                Some(i) => {
+                    self.selected = i;
+                    return self.choose(app);
+                }
+                // 其余按键（例如重开）照常交给游戏
+                None => app.queue_key(key),
+            },
+        }
+        Transition::None
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
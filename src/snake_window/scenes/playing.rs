@@ -0,0 +1,89 @@
+use crate::snake_game::game::Input;
+use crate::snake_window::app::{save_replay, save_run, App, SAVE_PATH};
+use crate::snake_window::scenes::board::{draw_board, draw_intro};
+use crate::snake_window::scenes::game_over::GameOver;
+use crate::snake_window::scenes::level_complete::LevelComplete;
+use crate::snake_window::scenes::paused::Paused;
+use crate::snake_window::scenes::{Scene, Transition};
+use piston_window::{Button, Context, Event, FocusEvent, G2d, PressEvent};
+use std::path::Path;
+
+/// 进入游戏时过渡动画的时长（秒）
+pub const INTRO_DURATION: f64 = 1.2;
+
+/// 游戏中：先播放进入动画，之后每帧推进这一局，暂停、过关和游戏结束时打开对应的界面
+pub struct Playing {
+    intro: f64,
+}
+
+impl Playing {
+    pub fn new() -> Playing {
+        Playing { intro: 0.0 }
+    }
+}
+
+impl Default for Playing {
+    fn default() -> Playing {
+        Playing::new()
+    }
+}
+
+/// 这一局进入游戏结束、过关或暂停状态时要打开的界面
+///
+/// 回放里的暂停只是记录下来的输入，`open_pause` 为假时不打开暂停界面。
+pub fn next_overlay(app: &App, open_pause: bool) -> Transition {
+    if app.game.is_game_over() {
+        Transition::Push(Box::new(GameOver::new()))
+    } else if app.game.waiting_next_level {
+        Transition::Push(Box::new(LevelComplete))
+    } else if open_pause && app.game.is_paused() {
+        Transition::Push(Box::new(Paused::new()))
+    } else {
+        Transition::None
+    }
+}
+
+impl Scene for Playing {
+    fn exit(&mut self, app: &mut App) {
+        // 离开时保存回放和存档
+        save_replay(&app.game);
+        if app.records_run {
+            save_run(&app.game);
+            app.has_save = Path::new(SAVE_PATH).exists();
+        }
+    }
+
+    fn update(&mut self, app: &mut App, dt: f64) -> Transition {
+        if self.intro < INTRO_DURATION {
+            self.intro += dt;
+            return Transition::None;
+        }
+        app.advance(dt);
+        next_overlay(app, true)
+    }
+
+    fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d) {
+        if self.intro < INTRO_DURATION {
+            draw_intro(app, self.intro / INTRO_DURATION, c, g);
+        } else {
+            draw_board(app, c, g);
+        }
+    }
+
+    fn handle_input(&mut self, app: &mut App, event: &Event) -> Transition {
+        // 窗口失去焦点时自动暂停；机器人对局没有人在看，照常进行
+        let pausing = app.game.is_paused() || app.pending_inputs.contains(&Input::TogglePause);
+        if event.focus_args() == Some(false) && app.bot.is_none() && !pausing {
+            app.pending_inputs.push(Input::TogglePause);
+        }
+        match event.press_args() {
+            Some(Button::Keyboard(key)) if app.is_quit(key) => Transition::Quit,
+            // 进入动画期间不接收输入
+            Some(Button::Keyboard(key)) if self.intro >= INTRO_DURATION => {
+                app.queue_key(key);
+                Transition::None
+            }
+            _ => Transition::None,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::snake_window::app::App;
+use crate::snake_window::input::handle_replay_key;
+use crate::snake_window::scenes::board::{draw_board, draw_intro};
+use crate::snake_window::scenes::playing::{next_overlay, INTRO_DURATION};
+use crate::snake_window::scenes::{Scene, Transition};
+use piston_window::{Button, Context, Event, G2d, PressEvent};
+
+/// 播放回放：画面和游戏中一样，按键只控制回放的暂停、快进和逐帧
+pub struct Replay {
+    intro: f64,
+}
+
+impl Replay {
+    pub fn new() -> Replay {
+        Replay { intro: 0.0 }
+    }
+}
+
+impl Default for Replay {
+    fn default() -> Replay {
+        Replay::new()
+    }
+}
+
+impl Scene for Replay {
+    fn update(&mut self, app: &mut App, dt: f64) -> Transition {
+        if self.intro < INTRO_DURATION {
+            self.intro += dt;
+            return Transition::None;
+        }
+        app.advance(dt);
+        next_overlay(app, false)
+    }
+
+    fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d) {
+        if self.intro < INTRO_DURATION {
+            draw_intro(app, self.intro / INTRO_DURATION, c, g);
+        } else {
+            draw_board(app, c, g);
+        }
+    }
+
+    fn handle_input(&mut self, app: &mut App, event: &Event) -> Transition {
+        match event.press_args() {
+            Some(Button::Keyboard(key)) if app.is_quit(key) => Transition::Quit,
+            Some(Button::Keyboard(key)) if self.intro >= INTRO_DURATION => {
+                if let Some(player) = &mut app.playback {
+                    handle_replay_key(&app.bindings, player, key);
+                }
+                Transition::None
+            }
+            _ => Transition::None,
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use crate::snake_game::game::{Game, VersusMode};
+use crate::snake_game::save::load_game;
+use crate::snake_window::app::{App, SAVE_PATH, VERSUS_FOOD_TARGET};
+use crate::snake_window::scenes::controls::Controls;
+use crate::snake_window::scenes::leaderboard::Leaderboard;
+use crate::snake_window::scenes::options::Options;
+use crate::snake_window::scenes::playing::Playing;
+use crate::snake_window::scenes::replay::Replay;
+use crate::snake_window::scenes::{Scene, Transition};
+use piston_window::{ellipse, rectangle, Button, Context, Event, G2d, Key, PressEvent, Transformed};
+use rand::Rng;
+use std::path::Path;
+
+// 漂浮恐怖符号
+#[derive(Clone)]
+struct FloatingSymbol {
+    ch: &'static str,
+    x: f64,
+    y: f64,
+    speed: f64,
+    angle: f64,
+    scale: f64,
+    alpha: f32,
+    angle_speed: f64,
+    scale_speed: f64,
+}
+
+// 血色雾气
+#[derive(Clone)]
+struct BloodMist {
+    x: f64,
+    y: f64,
+    rx: f64,
+    ry: f64,
+    dx: f64,
+    dy: f64,
+    drx: f64,
+    dry: f64,
+    alpha: f32,
+}
+
+/// 开始界面：按任意键开始，也可以进入排行榜、按键设置、设置和双人对战
+pub struct Start {
+    floating_symbols: Vec<FloatingSymbol>,
+    blood_mists: Vec<BloodMist>,
+}
+
+impl Start {
+    pub fn new() -> Start {
+        Start {
+            // ====== 漂浮恐怖符号初始化 ======
+            floating_symbols: vec![
+                FloatingSymbol { ch: "鬼", x: 120.0, y: 180.0, speed: 8.0, angle: 0.0, scale: 1.2, alpha: 0.32, angle_speed: 0.18, scale_speed: 0.07 },
+                FloatingSymbol { ch: "卍", x: 540.0, y: 320.0, speed: 10.0, angle: 0.0, scale: 1.0, alpha: 0.22, angle_speed: -0.13, scale_speed: 0.09 },
+                FloatingSymbol { ch: "手", x: 200.0, y: 500.0, speed: 7.0, angle: 0.0, scale: 1.3, alpha: 0.18, angle_speed: 0.22, scale_speed: -0.06 },
+                FloatingSymbol { ch: "鬼", x: 400.0, y: 600.0, speed: 9.0, angle: 0.0, scale: 0.9, alpha: 0.28, angle_speed: 0.15, scale_speed: 0.05 },
+            ],
+            // ====== 血色雾气初始化 ======
+            blood_mists: vec![
+                BloodMist { x: 180.0, y: 320.0, rx: 90.0, ry: 38.0, dx: 0.12, dy: 0.08, drx: 0.04, dry: 0.03, alpha: 0.13 },
+                BloodMist { x: 500.0, y: 180.0, rx: 60.0, ry: 28.0, dx: -0.09, dy: 0.11, drx: -0.03, dry: 0.02, alpha: 0.10 },
+                BloodMist { x: 350.0, y: 520.0, rx: 70.0, ry: 32.0, dx: 0.07, dy: -0.10, drx: 0.02, dry: -0.04, alpha: 0.09 },
+            ],
+        }
+    }
+}
+
+impl Default for Start {
+    fn default() -> Start {
+        Start::new()
+    }
+}
+
+impl Scene for Start {
+    fn resume(&mut self, app: &mut App) {
+        // 从一局游戏回到开始界面时，换一个随机种子准备新的一局
+        if app.playback.is_none() && app.game.get_tick() > 0 {
+            app.seed = rand::thread_rng().gen();
+            app.reset_game();
+            app.killcam.clear();
+            app.killcam_playback = None;
+        }
+    }
+
+    fn update(&mut self, _app: &mut App, _dt: f64) -> Transition {
+        Transition::None
+    }
+
+    fn draw(&mut self, app: &mut App, c: Context, g: &mut G2d) {
+        let (window_width, window_height, bg_time) = (app.window_width, app.window_height, app.bg_time);
+        let settings = &app.settings;
+        let glyphs = &mut app.glyphs;
+        // 动态血色渐变背景
+        let t = (bg_time * 0.5).sin() * 0.5 + 0.5;
+        let bg_color = [
+            (0.1 + 0.2 * t) as f32,
+            0.0,
+            (0.08 + 0.12 * t) as f32,
+            1.0,
+        ];
+        rectangle(bg_color, [0.0, 0.0, window_width as f64, window_height as f64], c.transform, g);
+
+        // ====== 全屏周期性闪光/闪烁 ======
+        let flash_period = 2.0;
+        let flash_phase = (bg_time % flash_period) / flash_period;
+        let flash_alpha = if flash_phase < 0.12 {
+            // 前12%时间闪光，alpha随sin变化
+            ((0.12 - flash_phase) / 0.12 * std::f64::consts::PI).sin().abs() as f32 * 0.55 * settings.effects as f32
+        } else { 0.0 };
+        if flash_alpha > 0.01 {
+            // 血色或白色闪光
+            let color = if flash_phase < 0.06 {
+                [1.0, 1.0, 1.0, flash_alpha]
+            } else {
+                [0.9, 0.1, 0.1, flash_alpha * 0.8]
+            };
+            rectangle(color, [0.0, 0.0, window_width as f64, window_height as f64], c.transform, g);
+        }
+
+        // ====== 主标题动态抖动/颜色突变 ======
+        let title = "梦魇贪吃蛇";
+        let title_size = 88;
+        let title_w = title.chars().count() as f64 * title_size as f64 * 0.9;
+        // 抖动参数
+        let shake_x = ((bg_time * 2.1).sin() * 8.0 + (bg_time * 1.3).cos() * 4.0) * settings.effects;
+        let shake_y = ((bg_time * 1.7).cos() * 6.0 + (bg_time * 2.7).sin() * 3.0) * settings.effects;
+        let scale = 1.0 + (bg_time * 0.9).sin() * 0.03;
+        // 颜色突变
+        let color_flash = ((bg_time * 0.7).sin().abs() > 0.98) as u8;
+        let title_color = if color_flash == 1 {
+            [1.0, 1.0, 1.0, 1.0]
+        } else {
+            [0.95, 0.0, 0.0, 1.0]
+        };
+        let title_x = (window_width as f64 - title_w * scale) / 2.0 - 70.0 + shake_x;
+        let title_y = 220.0 + shake_y;
+        // 渐变阴影
+        for i in 1..6 {
+            let alpha = 0.18 - 0.03 * (i as f32);
+            piston_window::text([0.7, 0.0, 0.0, alpha], title_size, title, glyphs, c.transform.trans(title_x + (i as f64), title_y + (i as f64)).scale(scale, scale), g).ok();
+        }
+        // 主标题
+        piston_window::text(title_color, title_size, title, glyphs, c.transform.trans(title_x, title_y).scale(scale, scale), g).unwrap();
+
+        // 居中副标题
+        let subtitle = "DREAM HORROR SNAKE";
+        let subtitle_size = 32;
+        let subtitle_w = subtitle.chars().count() as f64 * subtitle_size as f64 * 0.6;
+        let subtitle_x = (window_width as f64 - subtitle_w) / 2.0 - 40.0;
+        let subtitle_y = title_y + 70.0;
+        piston_window::text([0.9, 0.2, 0.2, 0.7], subtitle_size, subtitle, glyphs, c.transform.trans(subtitle_x, subtitle_y), g).ok();
+
+        // 居中恐怖提示（梦核闪光）
+        let tip = "按任意键进入噩梦";
+        let tip_size = 36;
+        let tip_w = tip.chars().count() as f64 * tip_size as f64 * 0.6;
+        let tip_x = (window_width as f64 - tip_w) / 2.0 - 40.0;
+        let tip_y = subtitle_y + 80.0;
+        let t = bg_time;
+        let flash_alpha = 0.5 + 0.5 * (t * 2.2).sin().abs(); // 0.5~1.0周期变化
+        let flash_color = [1.0, 0.2, 0.2, flash_alpha as f32];
+        let glow_color = [0.9, 0.7, 1.0, (0.3 + 0.5 * flash_alpha) as f32];
+        // 多层阴影
+        for i in 1..4 {
+            piston_window::text([0.0, 0.0, 0.0, 0.18], tip_size, tip, glyphs, c.transform.trans(tip_x + (i as f64), tip_y + (i as f64)), g).ok();
+        }
+        // 发光层
+        piston_window::text(glow_color, tip_size, tip, glyphs, c.transform.trans(tip_x, tip_y), g).ok();
+        // 主体闪光
+        piston_window::text(flash_color, tip_size, tip, glyphs, c.transform.trans(tip_x, tip_y), g).unwrap();
+        // 继续上次的存档
+        if app.has_save {
+            let cont = "按C继续上次的噩梦";
+            let cont_size = 28;
+            let cont_w = cont.chars().count() as f64 * cont_size as f64 * 0.6;
+            let cont_x = (window_width as f64 - cont_w) / 2.0 - 40.0;
+            piston_window::text([0.9, 0.7, 1.0, (0.4 + 0.4 * flash_alpha) as f32], cont_size, cont, glyphs, c.transform.trans(cont_x, tip_y + 56.0), g).ok();
+        }
+        // 排行榜入口
+        let board = "按L查看排行榜";
+        let board_w = board.chars().count() as f64 * 24.0 * 0.6;
+        let board_x = (window_width as f64 - board_w) / 2.0 - 40.0;
+        piston_window::text([0.8, 0.3, 0.3, 0.8], 24, board, glyphs, c.transform.trans(board_x, tip_y + 100.0), g).ok();
+        // 双人对战入口
+        let versus = format!("按V双人对战（最后存活）  按B双人对战（先吃{}个）", VERSUS_FOOD_TARGET);
+        let versus_w = versus.chars().count() as f64 * 20.0 * 0.6;
+        let versus_x = (window_width as f64 - versus_w) / 2.0 - 40.0;
+        piston_window::text([0.9, 0.6, 0.7, 0.8], 20, &versus, glyphs, c.transform.trans(versus_x, tip_y + 136.0), g).ok();
+        // 按键设置与设置入口
+        let controls = "按K设置按键  按O打开设置";
+        let controls_w = controls.chars().count() as f64 * 20.0 * 0.6;
+        let controls_x = (window_width as f64 - controls_w) / 2.0 - 40.0;
+        piston_window::text([0.8, 0.3, 0.3, 0.8], 20, controls, glyphs, c.transform.trans(controls_x, tip_y + 168.0), g).ok();
+
+        // 居中底部血池
+        let pool_w = 480.0;
+        let pool_h = 90.0;
+        let pool_x = (window_width as f64 - pool_w) / 2.0;
+        let pool_y = window_height as f64 - 90.0;
+        ellipse([0.5, 0.0, 0.0, 0.7], [pool_x, pool_y, pool_w, pool_h], c.transform, g);
+        // 居中血滴
+        let drop_x = window_width as f64 / 2.0 - 9.0;
+        ellipse([0.7, 0.0, 0.0, 0.7], [drop_x, pool_y - 30.0, 18.0, 24.0], c.transform, g);
+        ellipse([0.7, 0.0, 0.0, 0.5], [drop_x + 24.0, pool_y - 18.0, 8.0, 10.0], c.transform, g);
+        ellipse([0.7, 0.0, 0.0, 0.5], [drop_x - 24.0, pool_y - 18.0, 8.0, 10.0], c.transform, g);
+
+        // 左右两侧对称恐怖符号点缀
+        let side_y = window_height as f64 / 2.0 + 60.0;
+        piston_window::text([0.8, 0.0, 0.0, 0.4], 48, "卍", glyphs, c.transform.trans(40.0, side_y), g).ok();
+        piston_window::text([0.8, 0.0, 0.0, 0.4], 48, "鬼", glyphs, c.transform.trans(window_width as f64 - 80.0, side_y), g).ok();
+
+        // ====== 漂浮恐怖符号动态更新与绘制 ======
+        for sym in &mut self.floating_symbols {
+            sym.y += sym.speed * 0.016;
+            sym.angle += sym.angle_speed * 0.016;
+            sym.scale += sym.scale_speed * 0.016;
+            if sym.y > window_height as f64 + 60.0 {
+                sym.y = -60.0;
+            }
+            if sym.scale < 0.8 { sym.scale = 1.2; }
+            if sym.scale > 1.4 { sym.scale = 1.0; }
+            let color = [0.8, 0.0, 0.0, sym.alpha];
+            let transform = c.transform.trans(sym.x, sym.y).rot_rad(sym.angle).scale(sym.scale, sym.scale);
+            piston_window::text(color, 48, sym.ch, glyphs, transform, g).ok();
+        }
+
+        // ====== 血色雾气动态更新与绘制 ======
+        for mist in &mut self.blood_mists {
+            mist.x += mist.dx;
+            mist.y += mist.dy;
+            mist.rx += mist.drx;
+            mist.ry += mist.dry;
+            if mist.x < 0.0 || mist.x > window_width as f64 { mist.dx = -mist.dx; }
+            if mist.y < 0.0 || mist.y > window_height as f64 { mist.dy = -mist.dy; }
+            if mist.rx < 40.0 || mist.rx > 120.0 { mist.drx = -mist.drx; }
+            if mist.ry < 18.0 || mist.ry > 60.0 { mist.dry = -mist.dry; }
+            let color = [0.8, 0.1, 0.1, mist.alpha];
+            ellipse(color, [mist.x - mist.rx/2.0, mist.y - mist.ry/2.0, mist.rx, mist.ry], c.transform, g);
+        }
+
+        // ====== 屏幕边缘黑雾/红雾 ======
+        // 多层半透明渐变矩形/椭圆覆盖屏幕边缘
+        let edge_layers = 5;
+        for i in 0..edge_layers {
+            let k = i as f32 / (edge_layers as f32);
+            let alpha = 0.18 * (1.0 - k).powf(1.5) + 0.09 * (bg_time * (1.2 + k as f64)).sin().abs() as f32;
+            let color = [0.08 + 0.3 * k, 0.0, 0.0, alpha];
+            // 上
+            rectangle(color, [0.0, 0.0, window_width as f64, 32.0 + 24.0 * (k as f64)], c.transform, g);
+            // 下
+            rectangle(color, [0.0, window_height as f64 - (32.0 + 24.0 * (k as f64)), window_width as f64, 32.0 + 24.0 * (k as f64)], c.transform, g);
+            // 左
+            rectangle(color, [0.0, 0.0, 32.0 + 24.0 * (k as f64), window_height as f64], c.transform, g);
+            // 右
+            rectangle(color, [window_width as f64 - (32.0 + 24.0 * (k as f64)), 0.0, 32.0 + 24.0 * (k as f64), window_height as f64], c.transform, g);
+        }
+
+    }
+
+    fn handle_input(&mut self, app: &mut App, event: &Event) -> Transition {
+        let Some(Button::Keyboard(key)) = event.press_args() else {
+            return Transition::None;
+        };
+        if app.is_quit(key) {
+            return Transition::Quit;
+        }
+        match key {
+            Key::L => return Transition::Push(Box::new(Leaderboard)),
+            Key::K => return Transition::Push(Box::new(Controls::new())),
+            Key::O => return Transition::Push(Box::new(Options::new())),
+            _ => {}
+        }
+        // 双人对战使用同一个战役的第一关，重新取随机种子
+        let versus_mode = match key {
+            Key::V => Some(VersusMode::LastStanding),
+            Key::B => Some(VersusMode::FirstTo { food: VERSUS_FOOD_TARGET }),
+            _ => None,
+        };
+        if let Some(mode) = versus_mode {
+            if app.records_run {
+                app.game = Game::new_versus(app.game.get_campaign().clone(), rand::thread_rng().gen(), mode);
+            }
+        }
+        if key == Key::C && app.has_save {
+            match load_game(Path::new(SAVE_PATH)) {
+                Ok(saved) => app.game = saved,
+                Err(e) => eprintln!("读取存档失败，开始新的一局: {}", e),
+            }
+            app.has_save = false;
+        }
+        if app.playback.is_some() {
+            Transition::Push(Box::new(Replay::new()))
+        } else {
+            Transition::Push(Box::new(Playing::new()))
+        }
+    }
+}